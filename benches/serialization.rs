@@ -0,0 +1,53 @@
+//! Measures request deserialization, response construction and response serialization for representative payloads.
+//! Run with `cargo bench --features testing,audioplayer,apl`.
+
+use alexa_sdk::response::Speech;
+use alexa_sdk::testing::payloads;
+use alexa_sdk::{RequestEnvelope, ResponseEnvelope};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn deserialize_requests(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deserialize_request");
+    let requests = [
+        ("simple", payloads::intent_request()),
+        ("audioplayer", payloads::audioplayer_request()),
+        ("apl_user_event", payloads::apl_user_event_request(20)),
+    ];
+    for (name, req) in requests {
+        let json = serde_json::to_vec(&req).unwrap();
+        group.bench_function(name, |b| b.iter(|| serde_json::from_slice::<RequestEnvelope>(black_box(&json)).unwrap()));
+    }
+    group.finish();
+}
+
+fn serialize_responses(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize_response");
+    let responses: [(&str, ResponseEnvelope); 3] = [
+        ("simple", payloads::simple_response()),
+        ("audioplayer", payloads::audioplayer_response()),
+        ("big_apl", payloads::apl_response(100)),
+    ];
+    for (name, res) in responses {
+        group.bench_function(name, |b| b.iter(|| serde_json::to_vec(black_box(&res)).unwrap()));
+    }
+    group.finish();
+}
+
+/// Response constructors take `impl Into<String>`, so text which the skill has just formatted is moved into
+/// the response; passing it by reference, as the `&str` constructors required, costs a copy per string
+fn build_responses(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_response");
+    let text = || format!("Your order of {} large pizzas will arrive in {} minutes.", 3, 25).repeat(20);
+    group.bench_function("borrowed", |b| b.iter(|| {
+        let text = text();
+        ResponseEnvelope::ask(black_box("Order"), &text).reprompt(Speech::plain(&text))
+    }));
+    group.bench_function("owned", |b| b.iter(|| {
+        let text = text();
+        ResponseEnvelope::ask(black_box("Order"), text.clone()).reprompt(Speech::plain(text))
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, deserialize_requests, build_responses, serialize_responses);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+//! Runs a skill handler against a captured request and prints the response, e.g.
+//!
+//! ```sh
+//! cargo run --example local_invoke --features testing -- path/to/request.json
+//! ```
+
+use alexa_sdk::request::IntentType;
+use alexa_sdk::testing::{invoke_from_file, BoxError};
+use alexa_sdk::{RequestEnvelope, ResponseEnvelope};
+
+fn my_handler(req: RequestEnvelope) -> Result<ResponseEnvelope, BoxError> {
+    let res = match req.intent_type() {
+        Some(IntentType::Help) => ResponseEnvelope::simple("hello", "to say hello, tell me: say hello"),
+        Some(_) => ResponseEnvelope::simple("hello", "hello world"),
+        None => ResponseEnvelope::end(),
+    };
+    Ok(res)
+}
+
+fn main() -> Result<(), BoxError> {
+    let path = std::env::args().nth(1).ok_or("usage: local_invoke <request.json>")?;
+    let res = invoke_from_file(path, my_handler)?;
+    println!("{}", serde_json::to_string_pretty(&res)?);
+    Ok(())
+}
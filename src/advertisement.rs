@@ -0,0 +1,61 @@
+//! Alexa SDK Alexa.Advertisement interface datatypes, for skills in the in-skill advertising program.
+//!
+//! Alexa reports the outcome of an `InjectAds` directive with an `Alexa.Advertisement.AdCompleted` or
+//! `Alexa.Advertisement.AdNotRendered` request, see [`crate::request::RequestType`]. Both carry the
+//! directive's token in [`crate::request::Request::token`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::response::Directive;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InjectAdsDirective {
+    /// returned in the request which reports the outcome of the ad
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+impl InjectAdsDirective {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: Some(token.into()),
+        }
+    }
+}
+impl From<InjectAdsDirective> for Directive {
+    fn from(value: InjectAdsDirective) -> Self {
+        Directive::InjectAds(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::request::{RequestEnvelope, RequestType};
+
+    use super::*;
+
+    #[test]
+    fn inject_ads_round_trip() {
+        let directive: Directive = InjectAdsDirective::new("ad-1").into();
+        assert_eq!(
+            json!({ "type": "Alexa.Advertisement.InjectAds", "token": "ad-1" }),
+            serde_json::to_value(&directive).unwrap()
+        );
+
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "context": { "System": {} },
+            "request": {
+                "type": "Alexa.Advertisement.AdCompleted",
+                "requestId": "amzn1.echo-api.request.REQUEST",
+                "timestamp": "2025-03-17T23:27:29Z",
+                "locale": "en-US",
+                "token": "ad-1"
+            }
+        })).unwrap();
+        assert_eq!(req.request.request_type, RequestType::AdvertisementAdCompleted);
+        assert_eq!(req.request.token, Some(String::from("ad-1")));
+    }
+}
@@ -0,0 +1,1506 @@
+//! Alexa SDK Alexa.Presentation.APL interface datatypes, from [the specification](https://developer.amazon.com/en-US/docs/alexa/alexa-presentation-language/apl-interface.html).
+//!
+//! APL documents themselves are large, open-ended JSON structures, so they are carried as raw
+//! [`serde_json::Value`]s. The [standard commands](https://developer.amazon.com/en-US/docs/alexa/alexa-presentation-language/apl-standard-commands.html)
+//! sent with `ExecuteCommands` are typed, and can be composed with the constructors on [`Command`]:
+//! ```
+//! use alexa_sdk::apl::{Command, ExecuteCommandsDirective};
+//!
+//! let directive = ExecuteCommandsDirective::new("document-token")
+//!     .command(Command::sequential(vec![
+//!         Command::speak_item("title"),
+//!         Command::idle().delay(500),
+//!         Command::scroll_to_index("list", 3),
+//!     ]));
+//!
+//! assert_eq!(1, directive.commands.len());
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::declare_api_enum;
+use crate::request::{RequestContext, RequestEnvelope, RequestType, ViewportProfile};
+use crate::response::Directive;
+use crate::ssml::Ssml;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderDocumentDirective {
+    pub token: String,
+    pub document: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datasources: Option<HashMap<String, DataSource>>,
+}
+impl RenderDocumentDirective {
+    /// Constructs a directive rendering `document`, identified by `token`, with no data sources
+    pub fn new(token: impl Into<String>, document: Value) -> Self {
+        Self {
+            token: token.into(),
+            document,
+            datasources: None,
+        }
+    }
+
+    /// Adds a data source, which the document can bind to under `name`
+    pub fn datasource(mut self, name: impl Into<String>, datasource: DataSource) -> Self {
+        self.datasources.get_or_insert_with(HashMap::new).insert(name.into(), datasource);
+        self
+    }
+}
+impl From<RenderDocumentDirective> for Directive {
+    fn from(value: RenderDocumentDirective) -> Self {
+        Directive::RenderDocument(value)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteCommandsDirective {
+    pub token: String,
+    pub commands: Vec<Command>,
+}
+impl ExecuteCommandsDirective {
+    /// Constructs a directive with no commands, targeting the document rendered with `token`
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Appends a command to this directive
+    pub fn command(mut self, command: Command) -> Self {
+        self.commands.push(command);
+        self
+    }
+}
+impl From<ExecuteCommandsDirective> for Directive {
+    fn from(value: ExecuteCommandsDirective) -> Self {
+        Directive::ExecuteCommands(value)
+    }
+}
+
+/// The token which identifies a rendered APL document. `ExecuteCommands` directives are silently ignored
+/// unless their token is the token of the document on screen, so keep the token of a rendered document
+/// as an `AplToken` and derive later directives and checks from it:
+/// ```
+/// use alexa_sdk::apl::{AplToken, Command, RenderDocumentDirective};
+///
+/// let token = AplToken::generate("menu");
+/// let render = RenderDocumentDirective::new(token.as_str(), serde_json::json!({ "type": "APL" }));
+/// let execute = token.execute_commands().command(Command::speak_item("title"));
+/// assert_eq!(render.token, execute.token);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct AplToken(String);
+
+impl AplToken {
+    pub fn new(token: &str) -> Self {
+        Self(String::from(token))
+    }
+
+    /// Generates a token starting with `prefix` which is unique within this process, and unlikely to
+    /// repeat a token generated by an earlier process
+    pub fn generate(prefix: &str) -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        Self(format!("{}-{:x}-{}", prefix, nanos, COUNTER.fetch_add(1, Ordering::Relaxed)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Constructs an `ExecuteCommands` directive with no commands, targeting the document with this token
+    pub fn execute_commands(&self) -> ExecuteCommandsDirective {
+        ExecuteCommandsDirective::new(&self.0)
+    }
+
+    /// whether `req` is an `Alexa.Presentation.APL.UserEvent` raised by the document with this token
+    pub fn raised<C>(&self, req: &RequestEnvelope<C>) -> bool {
+        req.user_event().and_then(|e| e.token()) == Some(self.as_str())
+    }
+
+    /// the token of the APL document on the screen of the requesting device, from the
+    /// `Alexa.Presentation.APL` context, if any
+    pub fn on_screen<C: RequestContext>(req: &RequestEnvelope<C>) -> Option<Self> {
+        req.context.apl_token().map(Self::new)
+    }
+
+    /// whether the document with this token is on the screen of the requesting device, so that commands can
+    /// be sent to it with an `ExecuteCommands` directive rather than rendering it again
+    pub fn is_on_screen<C: RequestContext>(&self, req: &RequestEnvelope<C>) -> bool {
+        req.context.apl_token() == Some(self.as_str())
+    }
+}
+impl Display for AplToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+impl From<&RenderDocumentDirective> for AplToken {
+    fn from(value: &RenderDocumentDirective) -> Self {
+        Self::new(&value.token)
+    }
+}
+impl PartialEq<str> for AplToken {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+/// Embeds an APL document from a JSON file, with a path relative to the current source file like
+/// `include_str!`, as an [`AplDocument`]. A file which isn't a single JSON object, or whose brackets don't
+/// match, fails to compile:
+/// ```
+/// use alexa_sdk::include_apl;
+///
+/// let directive = include_apl!("../tests/fixtures/menu.json").directive("menu").unwrap();
+/// assert_eq!(directive.document["type"], "APL");
+/// ```
+#[macro_export]
+macro_rules! include_apl {
+    ($path:literal) => {{
+        const DOCUMENT: $crate::apl::AplDocument = $crate::apl::AplDocument::new(include_str!($path));
+        DOCUMENT
+    }};
+}
+
+/// The JSON source of an APL document, usually embedded with [`include_apl!`](crate::include_apl)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AplDocument {
+    source: &'static str,
+}
+impl AplDocument {
+    /// the deepest nesting of objects and arrays accepted by [`AplDocument::new`]
+    pub const MAX_DEPTH: usize = 256;
+
+    /// Checks the basic shape of `source`: a JSON object whose brackets match outside of strings, nested at
+    /// most [`AplDocument::MAX_DEPTH`] deep. Panics otherwise, which is a compile error when called in a
+    /// const context.
+    pub const fn new(source: &'static str) -> Self {
+        let bytes = source.as_bytes();
+        // the kinds of the open brackets, innermost last
+        let mut open = [0u8; Self::MAX_DEPTH];
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut closed = false;
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            i += 1;
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if b == b'\\' {
+                    escaped = true;
+                } else if b == b'"' {
+                    in_string = false;
+                }
+                continue;
+            }
+            match b {
+                b' ' | b'\t' | b'\n' | b'\r' => {}
+                _ if closed => panic!("APL document has content after its closing brace"),
+                b'{' | b'[' if depth > 0 || b == b'{' => {
+                    if depth == Self::MAX_DEPTH {
+                        panic!("APL document is nested too deeply");
+                    }
+                    open[depth] = b;
+                    depth += 1;
+                }
+                b'"' if depth > 0 => in_string = true,
+                b'}' | b']' if depth > 0 => {
+                    depth -= 1;
+                    if (open[depth] == b'{') != (b == b'}') {
+                        panic!("APL document has mismatched brackets");
+                    }
+                    closed = depth == 0;
+                }
+                _ if depth == 0 => panic!("APL document must be a JSON object"),
+                _ => {}
+            }
+        }
+        if !closed {
+            panic!("APL document has unbalanced brackets");
+        }
+        Self { source }
+    }
+
+    /// the JSON source of the document
+    pub const fn source(&self) -> &'static str {
+        self.source
+    }
+
+    /// parses the document, which fails only if the source is not valid JSON
+    pub fn document(&self) -> Result<Value, serde_json::Error> {
+        serde_json::from_str(self.source)
+    }
+
+    /// Constructs a directive rendering this document, identified by `token`, with no data sources
+    pub fn directive(&self, token: &str) -> Result<RenderDocumentDirective, serde_json::Error> {
+        Ok(RenderDocumentDirective::new(token, self.document()?))
+    }
+}
+
+/// An APL document together with the data sources it binds to
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewportDocument {
+    pub document: Value,
+    pub datasources: Option<HashMap<String, DataSource>>,
+}
+
+/// Chooses between APL documents designed for different viewport profiles:
+/// ```
+/// use alexa_sdk::apl::ViewportDocuments;
+/// use alexa_sdk::request::ViewportProfile;
+/// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+/// #     "version": "1.0",
+/// #     "context": { "System": {}, "Viewport": { "mode": "HUB", "shape": "ROUND", "pixelWidth": 480, "pixelHeight": 480, "dpi": 160 } },
+/// #     "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+/// # })).unwrap();
+///
+/// let documents = ViewportDocuments::new(serde_json::json!({ "type": "APL", "version": "2023.3" }), None)
+///     .document(ViewportProfile::HubRoundSmall, serde_json::json!({ "type": "APL", "version": "2023.3" }), None);
+///
+/// let directive = documents.render(&req, "main");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewportDocuments {
+    documents: Vec<(ViewportProfile, ViewportDocument)>,
+    default: ViewportDocument,
+}
+
+impl ViewportDocuments {
+    /// Starts a set of documents, with the document used for viewports which have no specific document
+    pub fn new(document: Value, datasources: Option<HashMap<String, DataSource>>) -> Self {
+        Self {
+            documents: Vec::new(),
+            default: ViewportDocument { document, datasources },
+        }
+    }
+
+    /// Adds the document to use for the given viewport profile
+    pub fn document(mut self, profile: ViewportProfile, document: Value, datasources: Option<HashMap<String, DataSource>>) -> Self {
+        self.documents.push((profile, ViewportDocument { document, datasources }));
+        self
+    }
+
+    /// Selects the document for the viewport of the requesting device, or `None` if the device doesn't support APL
+    pub fn select(&self, req: &RequestEnvelope) -> Option<&ViewportDocument> {
+        if !req.supports_interface("Alexa.Presentation.APL") {
+            return None;
+        }
+        let profile = req.context.viewport.as_ref().and_then(|v| v.profile());
+        let selected = self.documents.iter()
+            .find(|(p, _)| Some(*p) == profile)
+            .map(|(_, d)| d)
+            .unwrap_or(&self.default);
+        Some(selected)
+    }
+
+    /// Constructs a `RenderDocument` directive for the document selected by [`ViewportDocuments::select`]
+    pub fn render(&self, req: &RequestEnvelope, token: &str) -> Option<RenderDocumentDirective> {
+        let selected = self.select(req)?;
+        Some(RenderDocumentDirective {
+            token: String::from(token),
+            document: selected.document.clone(),
+            datasources: selected.datasources.clone(),
+        })
+    }
+}
+
+/// `Alexa.Presentation.APLT.RenderDocument`, which renders an APLT document on a character display,
+/// such as the clock of an Echo Dot
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AplTRenderDocumentDirective {
+    pub token: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_profile: Option<TargetProfile>,
+
+    pub document: Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datasources: Option<HashMap<String, DataSource>>,
+}
+impl From<AplTRenderDocumentDirective> for Directive {
+    fn from(value: AplTRenderDocumentDirective) -> Self {
+        Directive::AplTRenderDocument(value)
+    }
+}
+
+/// `Alexa.Presentation.APLT.ExecuteCommands`. Character displays support a subset of the standard
+/// commands, notably `Idle`, `Parallel`, `Sequential`, `SetValue`, `AutoPage`, `Scroll` and `SetPage`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AplTExecuteCommandsDirective {
+    pub token: String,
+    pub commands: Vec<Command>,
+}
+impl AplTExecuteCommandsDirective {
+    /// Constructs a directive with no commands, targeting the document rendered with `token`
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Appends a command to this directive
+    pub fn command(mut self, command: Command) -> Self {
+        self.commands.push(command);
+        self
+    }
+}
+impl From<AplTExecuteCommandsDirective> for Directive {
+    fn from(value: AplTExecuteCommandsDirective) -> Self {
+        Directive::AplTExecuteCommands(value)
+    }
+}
+
+declare_api_enum! {
+    TargetProfile => "SCREAMING_SNAKE_CASE" {
+        FourCharacterClock,
+        None
+    }
+}
+
+/// Name of the data source created by [`karaoke`]
+pub const KARAOKE_DATASOURCE: &str = "karaoke";
+
+/// Builds the directives for karaoke-style reading, where each block of text is highlighted while Alexa speaks it.
+///
+/// The returned `RenderDocument` directive carries a [`KARAOKE_DATASOURCE`] data source with one entry per
+/// block under `properties.blocks`. Transformers add `speech` and `text` properties to each entry, and each
+/// entry has an `id` which the document must assign to the component presenting it. The returned
+/// `ExecuteCommands` directive speaks the blocks in order with `SpeakItem`. For example, `document` could contain:
+/// ```json
+/// {
+///     "type": "Sequence",
+///     "data": "${karaoke.properties.blocks}",
+///     "item": { "type": "Text", "id": "${data.id}", "text": "${data.text}", "speech": "${data.speech}" }
+/// }
+/// ```
+pub fn karaoke(token: &str, document: Value, blocks: &[&str]) -> (RenderDocumentDirective, ExecuteCommandsDirective) {
+    let ids: Vec<String> = (0..blocks.len()).map(|i| format!("{}-{}", KARAOKE_DATASOURCE, i)).collect();
+    let entries: Vec<Value> = ids.iter().zip(blocks)
+        .map(|(id, text)| serde_json::json!({
+            "id": id,
+            "ssml": format!("<speak>{}</speak>", Ssml::escape(text)),
+        }))
+        .collect();
+
+    let mut properties = serde_json::Map::new();
+    properties.insert(String::from("blocks"), Value::Array(entries));
+    let datasource = ObjectDataSource {
+        properties,
+        ..Default::default()
+    }
+        .transformer(Transformer {
+            input_path: String::from("blocks.*.ssml"),
+            output_name: Some(String::from("speech")),
+            transformer: TransformerType::SsmlToSpeech,
+        })
+        .transformer(Transformer {
+            input_path: String::from("blocks.*.ssml"),
+            output_name: Some(String::from("text")),
+            transformer: TransformerType::SsmlToText,
+        });
+
+    let render = RenderDocumentDirective::new(token, document)
+        .datasource(KARAOKE_DATASOURCE, datasource.into());
+
+    let speak = ids.iter()
+        .map(|id| Command::SpeakItem(SpeakItemCommand {
+            common: CommandProperties::default(),
+            component_id: id.clone(),
+            align: Some(Align::Center),
+            highlight_mode: Some(HighlightMode::Line),
+            minimum_dwell_time: None,
+        }))
+        .collect();
+    let execute = ExecuteCommandsDirective::new(token).command(Command::sequential(speak));
+
+    (render, execute)
+}
+
+/// An APL data source, from [the specification](https://developer.amazon.com/en-US/docs/alexa/alexa-presentation-language/apl-data-source.html).
+/// Data sources which don't declare a known `type` are carried as raw JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum DataSource {
+    #[serde(rename = "object")]
+    Object(ObjectDataSource),
+
+    #[serde(rename = "list")]
+    List(ListDataSource),
+
+    #[serde(rename = "dynamicTokenList")]
+    DynamicTokenList(DynamicTokenListDataSource),
+
+    #[serde(untagged)]
+    Other(Value)
+}
+impl From<ObjectDataSource> for DataSource {
+    fn from(value: ObjectDataSource) -> Self {
+        DataSource::Object(value)
+    }
+}
+impl From<ListDataSource> for DataSource {
+    fn from(value: ListDataSource) -> Self {
+        DataSource::List(value)
+    }
+}
+impl From<DynamicTokenListDataSource> for DataSource {
+    fn from(value: DynamicTokenListDataSource) -> Self {
+        DataSource::DynamicTokenList(value)
+    }
+}
+
+/// A data source of type `dynamicTokenList`, holding the first page of a list whose further pages the
+/// device loads with `LoadTokenListData` requests, see [`TokenListPager`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamicTokenListDataSource {
+    pub list_id: String,
+    pub page_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_page_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backward_page_token: Option<String>,
+    pub items: Vec<Value>,
+}
+
+/// `Alexa.Presentation.APL.SendTokenListData`, answering a `LoadTokenListData` request with a page of items
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SendTokenListDataDirective {
+    pub token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_token: Option<String>,
+    pub list_id: String,
+    pub page_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+    pub items: Vec<Value>,
+}
+impl From<SendTokenListDataDirective> for Directive {
+    fn from(value: SendTokenListDataDirective) -> Self {
+        Directive::SendTokenListData(value)
+    }
+}
+
+/// Serves the items of a list a page at a time, as a [`DynamicTokenListDataSource`] for the first page and
+/// [`SendTokenListDataDirective`]s for the pages the device loads as the user scrolls. Page tokens encode the
+/// offset of their page, so nothing needs to be stored between requests:
+/// ```
+/// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+/// #     "version": "1.0",
+/// #     "context": { "System": {} },
+/// #     "request": { "type": "Alexa.Presentation.APL.LoadTokenListData", "requestId": "id",
+/// #         "timestamp": "2018-12-08T05:37:32Z", "token": "doc", "correlationToken": "c1",
+/// #         "listId": "songs", "pageToken": "offset:20", "count": 20 }
+/// # })).unwrap();
+/// use alexa_sdk::apl::TokenListPager;
+///
+/// let songs: Vec<String> = (0..50).map(|i| format!("song {}", i)).collect();
+/// let pager = TokenListPager::new("songs", &songs, 20);
+/// let first = pager.first_page().unwrap();
+/// assert_eq!(first.forward_page_token.as_deref(), Some("offset:20"));
+///
+/// let next = pager.respond(&req).unwrap().unwrap();
+/// assert_eq!(next.items.len(), 20);
+/// assert_eq!(next.next_page_token.as_deref(), Some("offset:40"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenListPager<'a, T> {
+    list_id: String,
+    items: &'a [T],
+    page_size: usize,
+}
+
+impl<'a, T: Serialize> TokenListPager<'a, T> {
+    pub fn new(list_id: &str, items: &'a [T], page_size: usize) -> Self {
+        Self {
+            list_id: String::from(list_id),
+            items,
+            page_size: page_size.max(1),
+        }
+    }
+
+    fn page_token(offset: usize) -> String {
+        format!("offset:{}", offset)
+    }
+
+    /// the items from `offset`, at most `count`, and the token of the page which follows them, if any
+    fn page(&self, offset: usize, count: usize) -> Result<(Vec<Value>, Option<String>), serde_json::Error> {
+        let end = offset.saturating_add(count).min(self.items.len());
+        let items = self.items.get(offset..end).unwrap_or_default()
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<Value>, _>>()?;
+        let next = (end < self.items.len()).then(|| Self::page_token(end));
+        Ok((items, next))
+    }
+
+    /// The data source to render with the list, holding its first page
+    pub fn first_page(&self) -> Result<DynamicTokenListDataSource, serde_json::Error> {
+        let (items, forward_page_token) = self.page(0, self.page_size)?;
+        Ok(DynamicTokenListDataSource {
+            list_id: self.list_id.clone(),
+            page_token: Self::page_token(0),
+            forward_page_token,
+            backward_page_token: None,
+            items,
+        })
+    }
+
+    /// Answers a `LoadTokenListData` request for this list with the requested page, of at most the page
+    /// size or the requested `count` items. Returns `None` for other requests, for other lists, and for page
+    /// tokens which this pager didn't issue.
+    pub fn respond(&self, req: &RequestEnvelope) -> Option<Result<SendTokenListDataDirective, serde_json::Error>> {
+        let request = &req.request;
+        if request.request_type != RequestType::AplLoadTokenListData || request.list_id.as_deref() != Some(self.list_id.as_str()) {
+            return None;
+        }
+        let page_token = request.page_token.as_deref()?;
+        let offset = page_token.strip_prefix("offset:")?.parse().ok()?;
+        let count = request.count.map_or(self.page_size, |c| (c as usize).clamp(1, self.page_size));
+        Some(self.page(offset, count).map(|(items, next_page_token)| SendTokenListDataDirective {
+            token: request.token.clone().unwrap_or_default(),
+            correlation_token: request.correlation_token.clone(),
+            list_id: self.list_id.clone(),
+            page_token: String::from(page_token),
+            next_page_token,
+            items,
+        }))
+    }
+}
+
+/// A data source of type `object`, whose `properties` are bound into the document
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectDataSource {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    pub properties: serde_json::Map<String, Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transformers: Option<Vec<Transformer>>,
+}
+impl ObjectDataSource {
+    /// Constructs an object data source from any serializable value, which must serialize to a JSON object
+    pub fn new<T: Serialize>(properties: &T) -> Result<Self, serde_json::Error> {
+        match serde_json::to_value(properties)? {
+            Value::Object(properties) => Ok(Self {
+                properties,
+                ..Default::default()
+            }),
+            _ => Err(serde::ser::Error::custom("object data source properties must serialize to a JSON object")),
+        }
+    }
+
+    /// Adds a transformer, see [`Transformer`]
+    pub fn transformer(mut self, transformer: Transformer) -> Self {
+        self.transformers.get_or_insert_with(Vec::new).push(transformer);
+        self
+    }
+}
+
+/// A data source of type `list`, presenting a page of `listItems`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDataSource {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_number_of_items: Option<u32>,
+
+    pub list_page: ListPage,
+}
+impl ListDataSource {
+    /// Constructs a list data source from a collection of serializable items
+    pub fn new<T: Serialize>(items: &[T]) -> Result<Self, serde_json::Error> {
+        let list_items = items.iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<Value>, _>>()?;
+        Ok(Self {
+            list_id: None,
+            total_number_of_items: Some(list_items.len() as u32),
+            list_page: ListPage { list_items },
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPage {
+    pub list_items: Vec<Value>,
+}
+
+/// Converts a data source property into a new property, such as speech from SSML
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Transformer {
+    pub input_path: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_name: Option<String>,
+
+    pub transformer: TransformerType,
+}
+
+declare_api_enum! {
+    TransformerType => "camelCase" {
+        SsmlToSpeech,
+        SsmlToText,
+        TextToHint,
+        TextToSpeech
+    }
+}
+
+/// An APL standard command, identified by its `type` property.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum Command {
+    AnimateItem(AnimateItemCommand),
+    AutoPage(AutoPageCommand),
+    ClearFocus(ClearFocusCommand),
+    Idle(IdleCommand),
+    Parallel(ParallelCommand),
+    Scroll(ScrollCommand),
+    ScrollToIndex(ScrollToIndexCommand),
+    Select(SelectCommand),
+    Sequential(SequentialCommand),
+    SetFocus(SetFocusCommand),
+    SetPage(SetPageCommand),
+    SetValue(SetValueCommand),
+    SpeakItem(SpeakItemCommand),
+
+    #[serde(untagged)]
+    Other(Value)
+}
+
+impl Command {
+    /// Constructs an `AnimateItem` command animating properties of the given component over `duration`
+    /// milliseconds, with linear easing; see [`AnimateItemCommand`] for other easing curves and repeats
+    pub fn animate_item(component_id: impl Into<String>, duration: u32, value: Vec<AnimatedProperty>) -> Self {
+        AnimateItemCommand::new(component_id, duration, value).into()
+    }
+
+    /// Constructs an `AutoPage` command for the pager with the given component id
+    pub fn auto_page(component_id: impl Into<String>) -> Self {
+        Command::AutoPage(AutoPageCommand {
+            common: CommandProperties::default(),
+            component_id: component_id.into(),
+            count: None,
+            duration: None,
+        })
+    }
+
+    /// Constructs a `ClearFocus` command, removing focus from the focused component
+    pub fn clear_focus() -> Self {
+        Command::ClearFocus(ClearFocusCommand {
+            common: CommandProperties::default(),
+        })
+    }
+
+    /// Constructs an `Idle` command; combine with [`Command::delay`] to pause a sequence
+    pub fn idle() -> Self {
+        Command::Idle(IdleCommand {
+            common: CommandProperties::default(),
+        })
+    }
+
+    /// Constructs a `Parallel` command running all of the given commands at once
+    pub fn parallel(commands: Vec<Command>) -> Self {
+        Command::Parallel(ParallelCommand {
+            common: CommandProperties::default(),
+            commands,
+        })
+    }
+
+    /// Constructs a `Scroll` command, scrolling the component by `distance` multiples of its height
+    pub fn scroll(component_id: impl Into<String>, distance: f64) -> Self {
+        Command::Scroll(ScrollCommand {
+            common: CommandProperties::default(),
+            component_id: component_id.into(),
+            distance,
+        })
+    }
+
+    /// Constructs a `ScrollToIndex` command for the given child index
+    pub fn scroll_to_index(component_id: impl Into<String>, index: i32) -> Self {
+        Command::ScrollToIndex(ScrollToIndexCommand {
+            common: CommandProperties::default(),
+            component_id: component_id.into(),
+            index,
+            align: None,
+        })
+    }
+
+    /// Constructs a `Select` command, running the first of the given commands whose `when` condition is true
+    pub fn select(commands: Vec<Command>) -> Self {
+        Command::Select(SelectCommand {
+            common: CommandProperties::default(),
+            commands,
+            data: None,
+            otherwise: None,
+        })
+    }
+
+    /// Constructs a `Sequential` command running the given commands in order
+    pub fn sequential(commands: Vec<Command>) -> Self {
+        Command::Sequential(SequentialCommand {
+            common: CommandProperties::default(),
+            commands,
+            catch: None,
+            finally: None,
+            repeat_count: None,
+        })
+    }
+
+    /// Constructs a `SetFocus` command, moving focus to the given component
+    pub fn set_focus(component_id: impl Into<String>) -> Self {
+        Command::SetFocus(SetFocusCommand {
+            common: CommandProperties::default(),
+            component_id: component_id.into(),
+        })
+    }
+
+    /// Constructs a `SetPage` command moving the pager to an absolute page
+    pub fn set_page(component_id: impl Into<String>, value: i32) -> Self {
+        Command::SetPage(SetPageCommand {
+            common: CommandProperties::default(),
+            component_id: component_id.into(),
+            position: Some(Position::Absolute),
+            value,
+        })
+    }
+
+    /// Constructs a `SetValue` command assigning `value` to a property of the given component
+    pub fn set_value(component_id: impl Into<String>, property: impl Into<String>, value: Value) -> Self {
+        Command::SetValue(SetValueCommand {
+            common: CommandProperties::default(),
+            component_id: Some(component_id.into()),
+            property: property.into(),
+            value,
+        })
+    }
+
+    /// Constructs a `SpeakItem` command for the given component id
+    pub fn speak_item(component_id: impl Into<String>) -> Self {
+        Command::SpeakItem(SpeakItemCommand {
+            common: CommandProperties::default(),
+            component_id: component_id.into(),
+            align: None,
+            highlight_mode: None,
+            minimum_dwell_time: None,
+        })
+    }
+
+    /// Sets the delay, in milliseconds, before this command runs. Has no effect on [`Command::Other`].
+    pub fn delay(mut self, milliseconds: u32) -> Self {
+        if let Some(common) = self.common_mut() {
+            common.delay = Some(milliseconds);
+        }
+        self
+    }
+
+    /// Sets the conditional expression for this command. Has no effect on [`Command::Other`].
+    pub fn when(mut self, expression: impl Into<String>) -> Self {
+        if let Some(common) = self.common_mut() {
+            common.when = Some(expression.into());
+        }
+        self
+    }
+
+    /// Returns the properties shared by all standard commands, or `None` for [`Command::Other`].
+    pub fn common_mut(&mut self) -> Option<&mut CommandProperties> {
+        match self {
+            Command::AnimateItem(c) => Some(&mut c.common),
+            Command::AutoPage(c) => Some(&mut c.common),
+            Command::ClearFocus(c) => Some(&mut c.common),
+            Command::Idle(c) => Some(&mut c.common),
+            Command::Parallel(c) => Some(&mut c.common),
+            Command::Scroll(c) => Some(&mut c.common),
+            Command::ScrollToIndex(c) => Some(&mut c.common),
+            Command::Select(c) => Some(&mut c.common),
+            Command::Sequential(c) => Some(&mut c.common),
+            Command::SetFocus(c) => Some(&mut c.common),
+            Command::SetPage(c) => Some(&mut c.common),
+            Command::SetValue(c) => Some(&mut c.common),
+            Command::SpeakItem(c) => Some(&mut c.common),
+            Command::Other(_) => None,
+        }
+    }
+}
+
+/// Properties which are common to all APL commands
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screen_lock: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimateItemCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub component_id: String,
+
+    /// in milliseconds
+    pub duration: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub easing: Option<Easing>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_count: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_mode: Option<RepeatMode>,
+
+    pub value: Vec<AnimatedProperty>,
+}
+impl AnimateItemCommand {
+    pub fn new(component_id: impl Into<String>, duration: u32, value: Vec<AnimatedProperty>) -> Self {
+        Self {
+            common: CommandProperties::default(),
+            component_id: component_id.into(),
+            duration,
+            easing: None,
+            repeat_count: None,
+            repeat_mode: None,
+            value,
+        }
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = Some(easing);
+        self
+    }
+
+    /// Plays the animation `count` more times after the first, restarting or reversing each time
+    pub fn repeat(mut self, count: u32, mode: RepeatMode) -> Self {
+        self.repeat_count = Some(count);
+        self.repeat_mode = Some(mode);
+        self
+    }
+}
+impl From<AnimateItemCommand> for Command {
+    fn from(value: AnimateItemCommand) -> Self {
+        Command::AnimateItem(value)
+    }
+}
+
+/// A property animated by [`AnimateItemCommand`], from its current value when `from` is `None`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "property", rename_all = "lowercase")]
+pub enum AnimatedProperty {
+    Opacity {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        from: Option<f64>,
+        to: f64,
+    },
+    /// `from` and `to` must list the same kinds of transform, in the same order
+    Transform {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        from: Option<Vec<Transform>>,
+        to: Vec<Transform>,
+    },
+}
+
+/// One step of a component transform. Angles are in degrees, translations in display-independent pixels.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Transform {
+    Rotate(f64),
+    Scale(f64),
+    ScaleX(f64),
+    ScaleY(f64),
+    SkewX(f64),
+    SkewY(f64),
+    TranslateX(f64),
+    TranslateY(f64),
+}
+
+declare_api_enum! {
+    /// The rate of change of an animation. Custom curves are `Other` values, see [`Easing::cubic_bezier`].
+    Easing {
+        Linear => "linear",
+        Ease => "ease",
+        EaseIn => "ease-in",
+        EaseOut => "ease-out",
+        EaseInOut => "ease-in-out"
+    }
+}
+
+impl Easing {
+    /// a cubic bezier curve from (0, 0) to (1, 1) with the given control points
+    pub fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        Easing::Other(format!("cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2))
+    }
+}
+
+declare_api_enum! {
+    RepeatMode => "lowercase" {
+        Restart,
+        Reverse
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoPageCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub component_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearFocusCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParallelCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub commands: Vec<Command>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub component_id: String,
+    pub distance: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollToIndexCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub component_id: String,
+    pub index: i32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align: Option<Align>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    /// candidates, of which the first whose `when` condition is true runs
+    pub commands: Vec<Command>,
+
+    /// when present, the candidates are evaluated for each item, bound to `${data}`, until one matches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<Value>>,
+
+    /// run when no candidate matches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otherwise: Option<Vec<Command>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SequentialCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub commands: Vec<Command>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub catch: Option<Vec<Command>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finally: Option<Vec<Command>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_count: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFocusCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub component_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPageCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub component_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+
+    pub value: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetValueCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component_id: Option<String>,
+
+    pub property: String,
+    pub value: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakItemCommand {
+    #[serde(flatten)]
+    pub common: CommandProperties,
+
+    pub component_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align: Option<Align>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_mode: Option<HighlightMode>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum_dwell_time: Option<u32>,
+}
+
+declare_api_enum! {
+    Align => "lowercase" {
+        First,
+        Center,
+        Last,
+        Visible
+    }
+}
+
+declare_api_enum! {
+    HighlightMode => "lowercase" {
+        Block,
+        Line
+    }
+}
+
+declare_api_enum! {
+    Position => "lowercase" {
+        Absolute,
+        Relative
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn apl_tokens() {
+        let token = AplToken::generate("menu");
+        assert!(token.as_str().starts_with("menu-"));
+        assert_ne!(token, AplToken::generate("menu"));
+
+        let render = RenderDocumentDirective::new(token.as_str(), json!({ "type": "APL" }));
+        assert_eq!(AplToken::from(&render), token);
+        assert_eq!(serde_json::to_value(&token).unwrap(), json!(token.to_string()));
+
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "context": {
+                "System": {},
+                "Alexa.Presentation.APL": { "token": token.as_str(), "version": "AplRuntime-1.9" }
+            },
+            "request": {
+                "type": "Alexa.Presentation.APL.UserEvent",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "token": token.as_str(),
+                "arguments": ["selected"]
+            }
+        })).unwrap();
+        assert!(token.raised(&req));
+        assert!(!AplToken::new("other").raised(&req));
+        assert!(token.is_on_screen(&req));
+        assert_eq!(AplToken::on_screen(&req), Some(token));
+    }
+
+    #[test]
+    fn response_with_apl() {
+        let req = |interfaces: Value| -> RequestEnvelope { serde_json::from_value(json!({
+            "version": "1.0",
+            "context": { "System": { "device": { "deviceId": "id", "supportedInterfaces": interfaces } } },
+            "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+        })).unwrap() };
+        let document = json!({ "type": "APL", "version": "2023.3" });
+        let datasources = HashMap::from([(String::from("data"), DataSource::Object(ObjectDataSource::new(&json!({ "title": "hi" })).unwrap()))]);
+
+        let token = AplToken::generate("document");
+        let res = crate::ResponseEnvelope::simple("hello", "hello world")
+            .with_apl(&req(json!({ "Alexa.Presentation.APL": {} })), &token, document.clone(), Some(datasources));
+        let directives = res.response.directives.unwrap();
+        match &directives[0] {
+            Directive::RenderDocument(d) => {
+                assert_eq!(d.token, token.as_str());
+                assert_eq!(d.datasources.as_ref().unwrap().len(), 1);
+            },
+            other => panic!("unexpected directive {:?}", other),
+        }
+        assert!(res.response.output_speech.is_some());
+
+        let res = crate::ResponseEnvelope::simple("hello", "hello world").with_apl(&req(json!({})), &token, document, None);
+        assert_eq!(res.response.directives, None);
+    }
+
+    #[test]
+    fn token_list_pages() {
+        let items: Vec<u32> = (0..5).collect();
+        let pager = TokenListPager::new("numbers", &items, 2);
+        let first = pager.first_page().unwrap();
+        assert_eq!(first.items, vec![json!(0), json!(1)]);
+        assert_eq!(serde_json::to_value(DataSource::from(first)).unwrap()["type"], "dynamicTokenList");
+
+        let load = |list_id: &str, page_token: &str| -> RequestEnvelope { serde_json::from_value(json!({
+            "version": "1.0",
+            "context": { "System": {} },
+            "request": {
+                "type": "Alexa.Presentation.APL.LoadTokenListData",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "token": "doc",
+                "correlationToken": "c1",
+                "listId": list_id,
+                "pageToken": page_token,
+                "count": 10
+            }
+        })).unwrap() };
+        let last: Directive = pager.respond(&load("numbers", "offset:4")).unwrap().unwrap().into();
+        assert_eq!(serde_json::to_value(&last).unwrap(), json!({
+            "type": "Alexa.Presentation.APL.SendTokenListData",
+            "token": "doc",
+            "correlationToken": "c1",
+            "listId": "numbers",
+            "pageToken": "offset:4",
+            "items": [4]
+        }));
+        assert_eq!(last.interface(), Some("Alexa.Presentation.APL"));
+        assert!(pager.respond(&load("numbers", "offset:9")).unwrap().unwrap().items.is_empty());
+        assert!(pager.respond(&load("other", "offset:2")).is_none());
+        assert!(pager.respond(&load("numbers", "page-2")).is_none());
+    }
+
+    #[test]
+    fn included_document() {
+        let directive = crate::include_apl!("../tests/fixtures/menu.json").directive("menu").unwrap();
+        assert_eq!(directive.document["type"], "APL");
+        assert_eq!(directive.token, "menu");
+
+        assert!(std::panic::catch_unwind(|| AplDocument::new("[1, 2]")).is_err());
+        assert!(std::panic::catch_unwind(|| AplDocument::new(r#"{"a": "}"}}"#)).is_err());
+        assert!(std::panic::catch_unwind(|| AplDocument::new(r#"{"a": "\"{"}"#)).is_ok());
+        assert!(std::panic::catch_unwind(|| AplDocument::new(r#"{"a": {}"#)).is_err());
+        assert!(std::panic::catch_unwind(|| AplDocument::new(r#"{"a": [1}]"#)).is_err());
+        assert!(std::panic::catch_unwind(|| AplDocument::new(r#"{"a": {"b": 1]}"#)).is_err());
+        assert!(std::panic::catch_unwind(|| AplDocument::new(r#"{"a": [{"b": [1]}]}"#)).is_ok());
+        let deep = format!("{{\"a\": {}{}}}", "[".repeat(AplDocument::MAX_DEPTH), "]".repeat(AplDocument::MAX_DEPTH));
+        assert!(std::panic::catch_unwind(|| AplDocument::new(Box::leak(deep.into_boxed_str()))).is_err());
+    }
+
+    #[test]
+    fn execute_commands_serialization() {
+        let directive: Directive = ExecuteCommandsDirective::new("doc")
+            .command(Command::sequential(vec![
+                Command::speak_item("title"),
+                Command::idle().delay(500),
+                Command::set_value("label", "text", json!("done")).when("${done}"),
+            ]))
+            .into();
+
+        assert_eq!(
+            json!({
+                "type": "Alexa.Presentation.APL.ExecuteCommands",
+                "token": "doc",
+                "commands": [
+                    {
+                        "type": "Sequential",
+                        "commands": [
+                            { "type": "SpeakItem", "componentId": "title" },
+                            { "type": "Idle", "delay": 500 },
+                            {
+                                "type": "SetValue",
+                                "when": "${done}",
+                                "componentId": "label",
+                                "property": "text",
+                                "value": "done"
+                            }
+                        ]
+                    }
+                ]
+            }),
+            serde_json::to_value(directive).unwrap()
+        );
+    }
+
+    #[test]
+    fn animation_and_focus_commands() {
+        let fade_in = AnimateItemCommand::new("logo", 1000, vec![
+            AnimatedProperty::Opacity { from: Some(0.0), to: 1.0 },
+            AnimatedProperty::Transform {
+                from: Some(vec![Transform::TranslateX(200.0), Transform::Rotate(-90.0)]),
+                to: vec![Transform::TranslateX(0.0), Transform::Rotate(0.0)],
+            },
+        ])
+        .easing(Easing::cubic_bezier(0.4, 0.0, 0.2, 1.0))
+        .repeat(2, RepeatMode::Reverse);
+        let mut select = Command::select(vec![Command::set_focus("next").when("${hasNext}")]);
+        if let Command::Select(c) = &mut select {
+            c.otherwise = Some(vec![Command::clear_focus()]);
+        }
+        let commands = vec![fade_in.into(), select];
+        let expected = json!([
+            {
+                "type": "AnimateItem",
+                "componentId": "logo",
+                "duration": 1000,
+                "easing": "cubic-bezier(0.4, 0, 0.2, 1)",
+                "repeatCount": 2,
+                "repeatMode": "reverse",
+                "value": [
+                    { "property": "opacity", "from": 0.0, "to": 1.0 },
+                    {
+                        "property": "transform",
+                        "from": [{ "translateX": 200.0 }, { "rotate": -90.0 }],
+                        "to": [{ "translateX": 0.0 }, { "rotate": 0.0 }]
+                    }
+                ]
+            },
+            {
+                "type": "Select",
+                "commands": [{ "type": "SetFocus", "when": "${hasNext}", "componentId": "next" }],
+                "otherwise": [{ "type": "ClearFocus" }]
+            }
+        ]);
+        assert_eq!(serde_json::to_value(&commands).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<Vec<Command>>(expected).unwrap(), commands);
+        assert_eq!(Easing::from("ease-in"), Easing::EaseIn);
+    }
+
+    #[test]
+    fn aplt_serialization() {
+        let directive: Directive = AplTRenderDocumentDirective {
+            token: String::from("clock"),
+            target_profile: Some(TargetProfile::FourCharacterClock),
+            document: json!({ "type": "APLT", "version": "1.0", "mainTemplate": { "item": { "type": "Text", "text": "1234" } } }),
+            datasources: None,
+        }.into();
+        assert_eq!(Some("Alexa.Presentation.APLT"), directive.interface());
+        assert_eq!(
+            json!({
+                "type": "Alexa.Presentation.APLT.RenderDocument",
+                "token": "clock",
+                "targetProfile": "FOUR_CHARACTER_CLOCK",
+                "document": { "type": "APLT", "version": "1.0", "mainTemplate": { "item": { "type": "Text", "text": "1234" } } }
+            }),
+            serde_json::to_value(&directive).unwrap()
+        );
+
+        let directive: Directive = AplTExecuteCommandsDirective::new("clock")
+            .command(Command::auto_page("pager"))
+            .into();
+        let value = serde_json::to_value(&directive).unwrap();
+        assert_eq!(json!("Alexa.Presentation.APLT.ExecuteCommands"), value["type"]);
+        assert!(matches!(serde_json::from_value(value).unwrap(), Directive::AplTExecuteCommands(_)));
+    }
+
+    #[test]
+    fn render_document_datasources() {
+        #[derive(Serialize)]
+        struct Item {
+            name: &'static str,
+        }
+
+        let directive: Directive = RenderDocumentDirective::new("doc", json!({ "type": "APL", "version": "2023.3" }))
+            .datasource("header", ObjectDataSource::new(&json!({ "title": "Planets" })).unwrap()
+                .transformer(Transformer {
+                    input_path: String::from("title"),
+                    output_name: Some(String::from("titleSpeech")),
+                    transformer: TransformerType::TextToSpeech,
+                })
+                .into())
+            .datasource("planets", ListDataSource::new(&[Item { name: "Mercury" }, Item { name: "Venus" }]).unwrap().into())
+            .into();
+
+        assert_eq!(
+            json!({
+                "type": "Alexa.Presentation.APL.RenderDocument",
+                "token": "doc",
+                "document": { "type": "APL", "version": "2023.3" },
+                "datasources": {
+                    "header": {
+                        "type": "object",
+                        "properties": { "title": "Planets" },
+                        "transformers": [
+                            { "inputPath": "title", "outputName": "titleSpeech", "transformer": "textToSpeech" }
+                        ]
+                    },
+                    "planets": {
+                        "type": "list",
+                        "totalNumberOfItems": 2,
+                        "listPage": {
+                            "listItems": [ { "name": "Mercury" }, { "name": "Venus" } ]
+                        }
+                    }
+                }
+            }),
+            serde_json::to_value(directive).unwrap()
+        );
+
+        assert!(ObjectDataSource::new(&vec![1, 2]).is_err());
+    }
+
+    #[test]
+    fn karaoke_directives() {
+        let (render, execute) = karaoke("story", json!({ "type": "APL", "version": "2023.3" }), &["Once upon a time", "Tom & Jerry"]);
+
+        let datasource = serde_json::to_value(&render.datasources.unwrap()[KARAOKE_DATASOURCE]).unwrap();
+        assert_eq!(
+            json!([
+                { "id": "karaoke-0", "ssml": "<speak>Once upon a time</speak>" },
+                { "id": "karaoke-1", "ssml": "<speak>Tom &amp; Jerry</speak>" }
+            ]),
+            datasource["properties"]["blocks"]
+        );
+        assert_eq!(json!("ssmlToSpeech"), datasource["transformers"][0]["transformer"]);
+
+        assert_eq!(
+            json!({
+                "token": "story",
+                "commands": [
+                    {
+                        "type": "Sequential",
+                        "commands": [
+                            { "type": "SpeakItem", "componentId": "karaoke-0", "align": "center", "highlightMode": "line" },
+                            { "type": "SpeakItem", "componentId": "karaoke-1", "align": "center", "highlightMode": "line" }
+                        ]
+                    }
+                ]
+            }),
+            serde_json::to_value(execute).unwrap()
+        );
+    }
+
+    #[test]
+    fn viewport_documents() {
+        let request = |viewport: Value| -> RequestEnvelope {
+            serde_json::from_value(json!({
+                "version": "1.0",
+                "context": {
+                    "System": {
+                        "device": {
+                            "deviceId": "amzn1.ask.device.DEVICE",
+                            "supportedInterfaces": { "Alexa.Presentation.APL": { "runtime": { "maxVersion": "2023.3" } } }
+                        }
+                    },
+                    "Viewport": viewport
+                },
+                "request": {
+                    "type": "LaunchRequest",
+                    "requestId": "amzn1.echo-api.request.id",
+                    "timestamp": "2018-12-08T05:37:32Z",
+                    "locale": "en-US"
+                }
+            })).unwrap()
+        };
+        let documents = ViewportDocuments::new(json!("default"), None)
+            .document(ViewportProfile::HubRoundSmall, json!("round"), None)
+            .document(ViewportProfile::HubLandscapeLarge, json!("large"), None);
+
+        let round = request(json!({ "mode": "HUB", "shape": "ROUND", "pixelWidth": 480, "pixelHeight": 480, "dpi": 160 }));
+        assert_eq!(json!("round"), documents.render(&round, "t").unwrap().document);
+
+        let large = request(json!({ "mode": "HUB", "shape": "RECTANGLE", "pixelWidth": 1280, "pixelHeight": 800, "dpi": 160 }));
+        assert_eq!(json!("large"), documents.render(&large, "t").unwrap().document);
+
+        let tv = request(json!({ "mode": "TV", "shape": "RECTANGLE", "pixelWidth": 1920, "pixelHeight": 1080, "dpi": 320 }));
+        assert_eq!(json!("default"), documents.render(&tv, "t").unwrap().document);
+
+        let mut headless = round.clone();
+        headless.context.system.device.as_mut().unwrap().supported_interfaces = None;
+        assert!(documents.render(&headless, "t").is_none());
+    }
+
+    #[test]
+    fn command_deserialization() {
+        let commands: Vec<Command> = serde_json::from_value(json!([
+            { "type": "ScrollToIndex", "componentId": "list", "index": 2, "align": "center" },
+            { "type": "SpeakItem", "componentId": "item", "highlightMode": "line" },
+            { "type": "OpenURL", "source": "https://localhost" }
+        ])).unwrap();
+
+        assert!(matches!(&commands[0], Command::ScrollToIndex(c) if c.index == 2 && c.align == Some(Align::Center)));
+        assert!(matches!(&commands[1], Command::SpeakItem(c) if c.highlight_mode == Some(HighlightMode::Line)));
+        assert!(matches!(&commands[2], Command::Other(v) if v["type"] == "OpenURL"));
+    }
+}
@@ -3,36 +3,68 @@
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::response::{Directive, PlayBehavior};
+use crate::request::{Offset, RequestEnvelope};
+use crate::response::{Directive, PlayBehavior, ValidationError};
 
 use super::display::Image;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct PlayDirective {
     pub audio_item: AudioItem,
     pub play_behavior: PlayBehavior,
 }
+impl PlayDirective {
+    pub fn new(play_behavior: PlayBehavior, audio_item: AudioItem) -> Self {
+        Self { audio_item, play_behavior }
+    }
+
+    /// Constructs an `ENQUEUE` directive for the stream which should follow the current one, in response to an
+    /// `AudioPlayer.PlaybackNearlyFinished` request. The `expectedPreviousToken` is taken from the token of the
+    /// stream reported by the request, and playback starts at the beginning of the next stream.
+    /// Returns `None` if the request doesn't identify the current stream.
+    pub fn enqueue_next(req: &RequestEnvelope, url: &str, token: &str) -> Option<Self> {
+        let previous = req.request.token.as_ref()
+            .or(req.context.audio_player.as_ref()?.token.as_ref())?;
+        Some(Self::new(
+            PlayBehavior::Enqueue,
+            AudioItem::new(Stream::new(url, token).expected_previous_token(previous)),
+        ))
+    }
+}
 impl From<PlayDirective> for Directive {
     fn from(value: PlayDirective) -> Self {
         Directive::Play(value)
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct AudioItem {
     pub stream: Stream,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<AudioItemMetadata>,
 }
+impl AudioItem {
+    pub fn new(stream: Stream) -> Self {
+        Self { stream, metadata: None }
+    }
+
+    pub fn metadata(mut self, metadata: AudioItemMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+}
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Stream {
     pub url: String,
     pub token: String,
-    pub offset_in_milliseconds: u64,
+    #[serde(default)]
+    pub offset_in_milliseconds: Offset,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expected_previous_token: Option<String>,
@@ -40,17 +72,69 @@ pub struct Stream {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub caption_data: Option<CaptionData>,
 }
+impl Stream {
+    /// Constructs a stream which plays from the beginning
+    pub fn new(url: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            token: token.into(),
+            offset_in_milliseconds: Offset::ZERO,
+            expected_previous_token: None,
+            caption_data: None,
+        }
+    }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+    pub fn offset(mut self, offset: Offset) -> Self {
+        self.offset_in_milliseconds = offset;
+        self
+    }
+
+    pub fn expected_previous_token(mut self, token: impl Into<String>) -> Self {
+        self.expected_previous_token = Some(token.into());
+        self
+    }
+
+    pub fn caption_data(mut self, captions: CaptionData) -> Self {
+        self.caption_data = Some(captions);
+        self
+    }
+}
+
+/// Captions for a stream, constructed with [`CaptionData::webvtt`]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct CaptionData {
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub data_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
 }
+impl CaptionData {
+    /// The caption type of WebVTT content, the only type Alexa supports
+    pub const WEBVTT: &'static str = "WEBVTT";
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+    /// Constructs captions from the content of a WebVTT file
+    pub fn webvtt(content: impl Into<String>) -> Self {
+        Self {
+            data_type: Some(String::from(Self::WEBVTT)),
+            content: Some(content.into()),
+        }
+    }
+
+    /// Checks that the captions have both content and a type, and that the type is `WEBVTT`.
+    /// Called by [`crate::ResponseEnvelope::validate`] for the captions of every `Play` directive.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match (self.data_type.as_deref(), &self.content) {
+            (Some(Self::WEBVTT), Some(_)) => Ok(()),
+            _ => Err(ValidationError::InvalidCaptionData),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct AudioItemMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -64,3 +148,138 @@ pub struct AudioItemMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub background_image: Option<Image>,
 }
+impl AudioItemMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    pub fn art(mut self, art: Image) -> Self {
+        self.art = Some(art);
+        self
+    }
+
+    pub fn background_image(mut self, image: Image) -> Self {
+        self.background_image = Some(image);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::request::RequestType;
+    use crate::ResponseEnvelope;
+
+    use super::*;
+
+    #[test]
+    fn enqueue_next() {
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "context": {
+                "AudioPlayer": {
+                    "token": "track-1",
+                    "offsetInMilliseconds": 170000,
+                    "playerActivity": "PLAYING"
+                },
+                "System": {}
+            },
+            "request": {
+                "type": "AudioPlayer.PlaybackNearlyFinished",
+                "requestId": "amzn1.echo-api.request.REQUEST",
+                "timestamp": "2025-03-17T23:27:29Z",
+                "locale": "en-US",
+                "token": "track-1",
+                "offsetInMilliseconds": 170000
+            }
+        })).unwrap();
+        assert_eq!(req.request.request_type, RequestType::AudioPlayerPlaybackNearlyFinished);
+
+        let directive: Directive = PlayDirective::enqueue_next(&req, "https://localhost/track-2.mp3", "track-2")
+            .unwrap()
+            .into();
+        assert_eq!(
+            json!({
+                "type": "AudioPlayer.Play",
+                "playBehavior": "ENQUEUE",
+                "audioItem": {
+                    "stream": {
+                        "url": "https://localhost/track-2.mp3",
+                        "token": "track-2",
+                        "expectedPreviousToken": "track-1",
+                        "offsetInMilliseconds": 0
+                    }
+                }
+            }),
+            serde_json::to_value(directive).unwrap()
+        );
+    }
+
+    #[test]
+    fn constructors() {
+        let directive = PlayDirective::new(
+            PlayBehavior::ReplaceAll,
+            AudioItem::new(Stream::new("https://localhost/track-1.mp3", "track-1").caption_data(CaptionData::webvtt("WEBVTT")))
+                .metadata(AudioItemMetadata::new().title("Track 1")),
+        );
+        assert_eq!(
+            json!({
+                "playBehavior": "REPLACE_ALL",
+                "audioItem": {
+                    "stream": {
+                        "url": "https://localhost/track-1.mp3",
+                        "token": "track-1",
+                        "offsetInMilliseconds": 0,
+                        "captionData": { "type": "WEBVTT", "content": "WEBVTT" }
+                    },
+                    "metadata": { "title": "Track 1" }
+                }
+            }),
+            serde_json::to_value(directive).unwrap()
+        );
+    }
+
+    #[test]
+    fn captions() {
+        let captions = CaptionData::webvtt("WEBVTT\n\n00:00.000 --> 00:02.000\nhello");
+        assert_eq!(serde_json::to_value(&captions).unwrap()["type"], "WEBVTT");
+        assert_eq!(captions.validate(), Ok(()));
+
+        let mut directive = PlayDirective {
+            play_behavior: PlayBehavior::ReplaceAll,
+            audio_item: AudioItem {
+                stream: Stream {
+                    url: String::from("https://localhost/track-1.mp3"),
+                    token: String::from("track-1"),
+                    offset_in_milliseconds: Offset::ZERO,
+                    expected_previous_token: None,
+                    caption_data: Some(CaptionData { data_type: None, content: captions.content }),
+                },
+                metadata: None,
+            },
+        };
+        let mut res = ResponseEnvelope::end();
+        res.add_directive(directive.clone().into());
+        assert_eq!(res.validate(), Err(vec![ValidationError::InvalidCaptionData]));
+
+        let mut empty = directive.clone();
+        empty.audio_item.stream.caption_data = Some(CaptionData { data_type: None, content: None });
+        assert_eq!(serde_json::to_value(&empty).unwrap()["audioItem"]["stream"]["captionData"], json!({}));
+
+        directive.audio_item.stream.caption_data = Some(CaptionData::webvtt(""));
+        let mut res = ResponseEnvelope::end();
+        res.add_directive(directive.into());
+        assert_eq!(res.validate(), Ok(()));
+    }
+}
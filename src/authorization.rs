@@ -0,0 +1,132 @@
+//! Types for the `Alexa.Authorization.Grant` skill event, which Alexa sends when a user links their account
+//! through an app-to-app or out-of-session flow, so the skill can obtain tokens for proactive API calls.
+//! This crate doesn't include an HTTP client; [`AuthorizationGrant::token_request`] builds the form body for
+//! the Login with Amazon token exchange, and [`LwaTokenResponse`] parses its reply.
+
+use serde::{Deserialize, Serialize};
+
+use crate::declare_api_enum;
+use crate::request::{RequestEnvelope, RequestType};
+
+/// The Login with Amazon endpoint which exchanges an authorization code for tokens
+pub const LWA_TOKEN_URL: &str = "https://api.amazon.com/auth/o2/token";
+
+/// The body of an `Alexa.Authorization.Grant` request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationGrant {
+    pub grant: Grant,
+    pub grantee: Grantee,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Grant {
+    #[serde(rename = "type")]
+    pub grant_type: GrantType,
+    pub code: String,
+}
+
+declare_api_enum! {
+    GrantType {
+        AuthorizationCode => "OAuth2.AuthorizationCode"
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Grantee {
+    #[serde(rename = "type")]
+    pub grantee_type: String,
+    pub token: String,
+}
+
+impl AuthorizationGrant {
+    /// Decodes the body of an `Alexa.Authorization.Grant` request, or returns `None` for other requests
+    pub fn from_request(req: &RequestEnvelope) -> Option<Result<Self, serde_json::Error>> {
+        match req.request.request_type {
+            RequestType::AlexaAuthorizationGrant => req.request.body.clone().map(serde_json::from_value),
+            _ => None,
+        }
+    }
+
+    /// Builds the `application/x-www-form-urlencoded` body to POST to [`LWA_TOKEN_URL`], exchanging the
+    /// granted code for an access token and refresh token, using the skill's client credentials from the
+    /// Alexa developer console
+    pub fn token_request(&self, client_id: &str, client_secret: &str) -> String {
+        [
+            ("grant_type", "authorization_code"),
+            ("code", self.grant.code.as_str()),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ]
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, form_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+    }
+}
+
+fn form_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'*' => out.push(b as char),
+            b' ' => out.push('+'),
+            b => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// The reply of the Login with Amazon token endpoint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LwaTokenResponse {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub token_type: String,
+    /// lifetime of the access token, in seconds
+    pub expires_in: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn grant_request() {
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "context": { "System": {} },
+            "request": {
+                "type": "Alexa.Authorization.Grant",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "body": {
+                    "grant": { "type": "OAuth2.AuthorizationCode", "code": "ANUbUK/Nx+Q" },
+                    "grantee": { "type": "BearerToken", "token": "access-token-from-skill" }
+                }
+            }
+        })).unwrap();
+        let grant = AuthorizationGrant::from_request(&req).unwrap().unwrap();
+        assert_eq!(grant.grant.grant_type, GrantType::AuthorizationCode);
+        assert_eq!(grant.grantee.token, "access-token-from-skill");
+        assert_eq!(
+            grant.token_request("amzn1.application-oa2-client.id", "s3cr3t&"),
+            "grant_type=authorization_code&code=ANUbUK%2FNx%2BQ&client_id=amzn1.application-oa2-client.id&client_secret=s3cr3t%26"
+        );
+    }
+
+    #[test]
+    fn token_response() {
+        let res: LwaTokenResponse = serde_json::from_value(json!({
+            "access_token": "Atza|access",
+            "refresh_token": "Atzr|refresh",
+            "token_type": "bearer",
+            "expires_in": 3600
+        })).unwrap();
+        assert_eq!(res.expires_in, 3600);
+    }
+}
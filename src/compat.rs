@@ -0,0 +1,58 @@
+//! Migration shims for skills written against the 0.x releases of the
+//! [parent crate](https://github.com/arienmalec/alexa_rust), where the envelope types were named
+//! `Request` and `Response` and responses were assembled with `add_*` methods.
+//!
+//! In most handlers, switching the imports is enough:
+//! ```
+//! use alexa_sdk::compat::{Request, Response, ResponseCompat};
+//! use alexa_sdk::response::Speech;
+//!
+//! fn handler(_req: &Request) -> Response {
+//!     let mut res = Response::new(false);
+//!     res.add_speech(Speech::plain("hello"));
+//!     res.add_reprompt(Speech::plain("are you there?"));
+//!     res
+//! }
+//!
+//! let res = handler(&serde_json::from_str(r#"{
+//!     "version": "1.0",
+//!     "context": { "System": {} },
+//!     "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+//! }"#).unwrap());
+//! assert!(res.response.reprompt.is_some());
+//! ```
+
+use crate::response::{Card, Reprompt, Speech};
+use crate::{RequestEnvelope, ResponseEnvelope};
+
+/// The 0.x name of [`RequestEnvelope`]
+pub type Request = RequestEnvelope;
+
+/// The 0.x name of [`ResponseEnvelope`]
+pub type Response = ResponseEnvelope;
+
+/// In-place setters with the 0.x names, as an alternative to the consuming builder methods of [`ResponseEnvelope`]
+pub trait ResponseCompat {
+    /// sets the output speech, see [`ResponseEnvelope::speech`]
+    fn add_speech(&mut self, speech: Speech);
+
+    /// sets the card, see [`ResponseEnvelope::card`]
+    fn add_card(&mut self, card: Card);
+
+    /// sets the reprompt speech, see [`ResponseEnvelope::reprompt`]
+    fn add_reprompt(&mut self, speech: Speech);
+}
+
+impl ResponseCompat for ResponseEnvelope {
+    fn add_speech(&mut self, speech: Speech) {
+        self.response.output_speech = Some(speech);
+    }
+
+    fn add_card(&mut self, card: Card) {
+        self.response.card = Some(card);
+    }
+
+    fn add_reprompt(&mut self, speech: Speech) {
+        self.response.reprompt.get_or_insert_with(Reprompt::default).output_speech = Some(speech);
+    }
+}
@@ -0,0 +1,155 @@
+//! A minimal local HTTP server for developing skills, e.g. behind the ASK CLI's local debugging or an ngrok
+//! tunnel. Requests are passed to the handler without verifying their signature or timestamp, so this server
+//! must never be used to host a live skill.
+//!
+//! ```no_run
+//! use alexa_sdk::{RequestEnvelope, ResponseEnvelope};
+//!
+//! fn handler(_req: RequestEnvelope) -> Result<ResponseEnvelope, std::io::Error> {
+//!     Ok(ResponseEnvelope::simple("hello", "hello world"))
+//! }
+//!
+//! alexa_sdk::dev::serve(handler, "127.0.0.1:3000").unwrap();
+//! ```
+
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::{RequestEnvelope, ResponseEnvelope};
+
+/// Maximum accepted request body size, well above the size of any request Alexa sends
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// How long [`serve`] waits for a client to send its request or accept the response
+pub const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Listens on `addr` and answers each `POST` containing an Alexa request with the response of `handler`.
+/// Connections are handled one at a time, each with a read and write timeout of [`IO_TIMEOUT`], so a client
+/// which stalls can't block the server. A connection which fails is skipped, and reported as a `WARN` event
+/// with the `tracing` feature. This function only returns if `addr` can't be bound.
+pub fn serve<F, E, A>(handler: F, addr: A) -> std::io::Result<()>
+where
+    F: Fn(RequestEnvelope) -> Result<ResponseEnvelope, E>,
+    E: Display,
+    A: ToSocketAddrs,
+{
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| {
+            stream.set_read_timeout(Some(IO_TIMEOUT))?;
+            stream.set_write_timeout(Some(IO_TIMEOUT))?;
+            let reader = BufReader::new(stream.try_clone()?);
+            handle(reader, &stream, &handler)
+        });
+        if let Err(_error) = result {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %_error, "alexa_sdk::dev connection failed");
+        }
+    }
+    Ok(())
+}
+
+fn handle<R, W, F, E>(mut reader: R, mut writer: W, handler: &F) -> std::io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: Fn(RequestEnvelope) -> Result<ResponseEnvelope, E>,
+    E: Display,
+{
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let (status, body) = if !request_line.starts_with("POST ") {
+        ("405 Method Not Allowed", String::from("only POST is supported"))
+    } else if content_length > MAX_BODY_BYTES {
+        ("413 Payload Too Large", format!("request body is larger than {} bytes", MAX_BODY_BYTES))
+    } else {
+        let mut bytes = vec![0; content_length];
+        reader.read_exact(&mut bytes)?;
+        match serde_json::from_slice::<RequestEnvelope>(&bytes) {
+            Err(e) => ("400 Bad Request", format!("invalid request: {}", e)),
+            Ok(req) => match handler(req) {
+                Err(e) => ("500 Internal Server Error", e.to_string()),
+                Ok(res) => ("200 OK", serde_json::to_string(&res)?),
+            },
+        }
+    };
+    let content_type = if status.starts_with("200") { "application/json" } else { "text/plain" };
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn post(body: &str) -> String {
+        let request = format!("POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        let mut response = Vec::new();
+        handle(request.as_bytes(), &mut response, &|req: RequestEnvelope| match req.locale() {
+            Some(locale) => Ok(ResponseEnvelope::simple("locale", locale.to_string())),
+            None => Err("no locale"),
+        }).unwrap();
+        String::from_utf8(response).unwrap()
+    }
+
+    #[test]
+    fn answers_request() {
+        let response = post(&json!({
+            "version": "1.0",
+            "context": { "System": {} },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US"
+            }
+        }).to_string());
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let body: ResponseEnvelope = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body.response.card.unwrap().content.unwrap(), "en-US");
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert!(post("{}").starts_with("HTTP/1.1 400 Bad Request\r\n"));
+
+        let response = post(&json!({
+            "version": "1.0",
+            "context": { "System": {} },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z"
+            }
+        }).to_string());
+        assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+        assert!(response.ends_with("no locale"));
+
+        let mut response = Vec::new();
+        handle("GET / HTTP/1.1\r\n\r\n".as_bytes(), &mut response, &|_| -> Result<ResponseEnvelope, String> {
+            Ok(ResponseEnvelope::end())
+        }).unwrap();
+        assert!(String::from_utf8(response).unwrap().starts_with("HTTP/1.1 405"));
+    }
+}
@@ -0,0 +1,263 @@
+//! Alexa SDK Dialog interface datatypes, from [the specification](https://developer.amazon.com/en-US/docs/alexa/custom-skills/dialog-interface-reference.html).
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+use crate::declare_api_enum;
+use crate::request::{Intent, Slot};
+use crate::response::Directive;
+
+/// `Dialog.DelegateRequest`, which hands the dialog over to Alexa Conversations, or back to the skill
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegateRequestDirective {
+    pub target: DelegationTarget,
+    pub period: DelegationPeriod,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_request: Option<UpdatedRequest>,
+}
+
+impl DelegateRequestDirective {
+    /// Delegates to the Alexa Conversations dialog, starting it with the named input, e.g. a dialog act
+    pub fn to_conversations(input_name: impl Into<String>) -> Self {
+        Self {
+            target: DelegationTarget::Conversations,
+            period: DelegationPeriod::default(),
+            updated_request: Some(UpdatedRequest::InputRequest {
+                input: DialogInput {
+                    name: input_name.into(),
+                    slots: None,
+                },
+            }),
+        }
+    }
+
+    /// Delegates from Alexa Conversations back to the skill, which receives `intent` in an `IntentRequest`
+    pub fn to_skill(intent: Intent) -> Self {
+        Self {
+            target: DelegationTarget::Skill,
+            period: DelegationPeriod::default(),
+            updated_request: Some(UpdatedRequest::IntentRequest { intent }),
+        }
+    }
+}
+
+impl From<DelegateRequestDirective> for Directive {
+    fn from(value: DelegateRequestDirective) -> Self {
+        Directive::DelegateRequest(value)
+    }
+}
+
+declare_api_enum! {
+    DelegationTarget {
+        Conversations => "AMAZON.Conversations",
+        Skill => "skill"
+    }
+}
+
+/// How long the delegation lasts
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationPeriod {
+    pub until: DelegationPeriodUntil,
+}
+
+declare_api_enum! {
+    DelegationPeriodUntil => "SCREAMING_SNAKE_CASE" {
+        ExplicitReturn
+    }
+}
+#[allow(clippy::derivable_impls)]
+impl Default for DelegationPeriodUntil {
+    fn default() -> Self {
+        Self::ExplicitReturn
+    }
+}
+
+/// The request the delegation target receives in place of the current one
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum UpdatedRequest {
+    #[serde(rename = "Dialog.InputRequest")]
+    InputRequest { input: DialogInput },
+
+    IntentRequest { intent: Intent },
+
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DialogInput {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<HashMap<String, Slot>>,
+}
+
+/// `Dialog.UpdateDynamicEntities`, which adds entities to custom slot types for the rest of the session,
+/// such as the user's own playlists, so that Alexa can resolve them:
+/// ```
+/// use alexa_sdk::dialog::{DynamicSlotType, UpdateDynamicEntitiesDirective};
+///
+/// let directive = UpdateDynamicEntitiesDirective::replace(vec![
+///     DynamicSlotType::new("AirportSlotType")
+///         .entity("BOS", "Logan International Airport", &["Boston Logan"])
+///         .entity("LGA", "LaGuardia Airport", &[]),
+/// ]);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDynamicEntitiesDirective {
+    pub update_behavior: UpdateBehavior,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub types: Option<Vec<DynamicSlotType>>,
+}
+
+impl UpdateDynamicEntitiesDirective {
+    /// Replaces any dynamic entities of the session with the entities of `types`
+    pub fn replace(types: Vec<DynamicSlotType>) -> Self {
+        Self {
+            update_behavior: UpdateBehavior::Replace,
+            types: Some(types),
+        }
+    }
+
+    /// Removes all dynamic entities of the session
+    pub fn clear() -> Self {
+        Self {
+            update_behavior: UpdateBehavior::Clear,
+            types: None,
+        }
+    }
+}
+
+impl From<UpdateDynamicEntitiesDirective> for Directive {
+    fn from(value: UpdateDynamicEntitiesDirective) -> Self {
+        Directive::UpdateDynamicEntities(value)
+    }
+}
+
+declare_api_enum! {
+    UpdateBehavior => "SCREAMING_SNAKE_CASE" {
+        Replace,
+        Clear
+    }
+}
+
+/// The dynamic entities of one custom slot type
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamicSlotType {
+    pub name: String,
+    pub values: Vec<DynamicEntity>,
+}
+
+impl DynamicSlotType {
+    /// Starts a list of entities for the custom slot type `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Adds an entity, with the id it resolves to, its canonical value and synonyms
+    pub fn entity(mut self, id: impl Into<String>, value: impl Into<String>, synonyms: &[&str]) -> Self {
+        self.values.push(DynamicEntity {
+            id: Some(id.into()),
+            name: EntityName {
+                value: value.into(),
+                synonyms: (!synonyms.is_empty()).then(|| synonyms.iter().map(|s| String::from(*s)).collect()),
+            },
+        });
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamicEntity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: EntityName,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityName {
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synonyms: Option<Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn delegate_to_conversations() {
+        let directive: Directive = DelegateRequestDirective::to_conversations("Weather.Forecast").into();
+        assert_eq!(
+            json!({
+                "type": "Dialog.DelegateRequest",
+                "target": "AMAZON.Conversations",
+                "period": { "until": "EXPLICIT_RETURN" },
+                "updatedRequest": {
+                    "type": "Dialog.InputRequest",
+                    "input": { "name": "Weather.Forecast" }
+                }
+            }),
+            serde_json::to_value(&directive).unwrap()
+        );
+        assert_eq!(directive.interface(), None);
+    }
+
+    #[test]
+    fn delegate_to_skill() {
+        let intent = Intent::builder("OrderPizza").slot("size", "large").build();
+        let value = serde_json::to_value(Directive::from(DelegateRequestDirective::to_skill(intent))).unwrap();
+        assert_eq!(value["target"], json!("skill"));
+        assert_eq!(value["updatedRequest"]["type"], json!("IntentRequest"));
+        assert_eq!(value["updatedRequest"]["intent"]["slots"]["size"]["value"], json!("large"));
+
+        let directive: Directive = serde_json::from_value(value).unwrap();
+        match directive {
+            Directive::DelegateRequest(d) => match d.updated_request {
+                Some(UpdatedRequest::IntentRequest { intent }) => assert_eq!(intent.name.as_str(), "OrderPizza"),
+                other => panic!("unexpected updated request {:?}", other),
+            },
+            other => panic!("unexpected directive {:?}", other),
+        }
+    }
+
+    #[test]
+    fn update_dynamic_entities() {
+        let directive: Directive = UpdateDynamicEntitiesDirective::replace(vec![
+            DynamicSlotType::new("AirportSlotType").entity("BOS", "Logan International Airport", &["Boston Logan"]),
+        ]).into();
+        assert_eq!(
+            json!({
+                "type": "Dialog.UpdateDynamicEntities",
+                "updateBehavior": "REPLACE",
+                "types": [{
+                    "name": "AirportSlotType",
+                    "values": [{
+                        "id": "BOS",
+                        "name": { "value": "Logan International Airport", "synonyms": ["Boston Logan"] }
+                    }]
+                }]
+            }),
+            serde_json::to_value(&directive).unwrap()
+        );
+        assert_eq!(directive.interface(), None);
+
+        assert_eq!(
+            json!({ "type": "Dialog.UpdateDynamicEntities", "updateBehavior": "CLEAR" }),
+            serde_json::to_value(Directive::from(UpdateDynamicEntitiesDirective::clear())).unwrap()
+        );
+    }
+}
@@ -12,9 +12,24 @@
 
 use serde::{Deserialize, Serialize};
 
+use std::fmt::Display;
+use std::str::FromStr;
+
 use crate::declare_api_enum;
+use crate::request::{RequestEnvelope, RequestType};
+use crate::response;
+use crate::response::{validate_image_url, ImageUrlError};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+/// An image with instances of several sizes. Converts to and from the card [`response::Image`], whose small
+/// and large urls map to instances of size [`ImageSize::Small`] and [`ImageSize::Large`]:
+/// ```
+/// use alexa_sdk::{display, response};
+///
+/// let card = response::Image::from_base_url("https://cdn.example.com/pizza", "{base}-{size}.png").unwrap();
+/// let art = display::Image::from(&card).content_description("a pizza");
+/// assert_eq!(response::Image::try_from(&art).unwrap(), card);
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Image {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -23,7 +38,61 @@ pub struct Image {
     pub sources: Vec<ImageInstance>
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+impl Image {
+    /// Constructs an image from its instances, without a description
+    pub fn new(sources: Vec<ImageInstance>) -> Self {
+        Self {
+            content_description: None,
+            sources,
+        }
+    }
+
+    /// Sets the description of the image, for screen readers
+    pub fn content_description(mut self, description: impl Into<String>) -> Self {
+        self.content_description = Some(description.into());
+        self
+    }
+
+    /// The instance whose width is closest to the recommended width of `size`. Instances of unknown
+    /// width are only chosen if no instance has a known width.
+    pub fn closest(&self, size: &ImageSize) -> Option<&ImageInstance> {
+        let target = i32::from(size.dimensions()?.0);
+        self.sources.iter()
+            .filter_map(|i| i.width().map(|w| (i, (i32::from(w) - target).abs())))
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(i, _)| i)
+            .or(self.sources.first())
+    }
+}
+
+impl From<&response::Image> for Image {
+    fn from(value: &response::Image) -> Self {
+        let small = value.small_image_url.as_deref().map(|url| ImageInstance::sized(url, ImageSize::Small));
+        let large = value.large_image_url.as_deref().map(|url| ImageInstance::sized(url, ImageSize::Large));
+        Self::new(small.into_iter().chain(large).collect())
+    }
+}
+
+impl From<response::Image> for Image {
+    fn from(value: response::Image) -> Self {
+        Self::from(&value)
+    }
+}
+
+/// Picks the instances closest to the small and large card image sizes, see [`Image::closest`].
+/// Fails with [`ImageUrlError::Empty`] for an image without instances.
+impl TryFrom<&Image> for response::Image {
+    type Error = ImageUrlError;
+
+    fn try_from(value: &Image) -> Result<Self, Self::Error> {
+        let url = |size| value.closest(&size).map(|i| i.url.clone()).ok_or(ImageUrlError::Empty);
+        Ok(response::Image::new()
+            .small_image_url(url(ImageSize::Small)?)
+            .large_image_url(url(ImageSize::Large)?))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageInstance {
     pub url: String,
@@ -38,6 +107,29 @@ pub struct ImageInstance {
     pub height_pixels: Option<u16>,
 }
 
+impl ImageInstance {
+    /// Constructs an image instance of a standard size, with the pixel dimensions recommended for that size
+    pub fn sized(url: impl Into<String>, size: ImageSize) -> Self {
+        let dimensions = size.dimensions();
+        Self {
+            url: url.into(),
+            size: Some(size),
+            width_pixels: dimensions.map(|(w, _)| w),
+            height_pixels: dimensions.map(|(_, h)| h),
+        }
+    }
+
+    /// Checks the url of this image with [`validate_image_url`]
+    pub fn validate(&self) -> Result<(), ImageUrlError> {
+        validate_image_url(&self.url)
+    }
+
+    /// the width in pixels of this instance, or else the recommended width of its size
+    pub fn width(&self) -> Option<u16> {
+        self.width_pixels.or_else(|| Some(self.size.as_ref()?.dimensions()?.0))
+    }
+}
+
 declare_api_enum! {
     ImageSize => "SCREAMING_SNAKE_CASE" {
         XSmall,
@@ -47,3 +139,260 @@ declare_api_enum! {
         XLarge
     }
 }
+
+impl ImageSize {
+    /// The recommended `(width, height)` in pixels for images of this size, or `None` for unrecognized sizes
+    pub fn dimensions(&self) -> Option<(u16, u16)> {
+        match self {
+            ImageSize::XSmall => Some((480, 320)),
+            ImageSize::Small => Some((720, 480)),
+            ImageSize::Medium => Some((960, 640)),
+            ImageSize::Large => Some((1200, 800)),
+            ImageSize::XLarge => Some((1920, 1280)),
+            ImageSize::Other(_) => None,
+        }
+    }
+}
+
+/// A structured token identifying a selectable element, such as a list item, encoded as
+/// `kind[:index[:id[:payload]]]`. Colons and percent signs within components are percent-encoded,
+/// so any string can be carried safely. An absent `id` or `payload` is left blank, while an empty one is
+/// written as a lone `%`. The same encoding can be used for the arguments of an APL `SendEvent`:
+/// ```
+/// use alexa_sdk::display::SelectionToken;
+///
+/// let token = SelectionToken::new("item").index(3).id("B00X:1");
+/// assert_eq!(token.to_string(), "item:3:B00X%3A1");
+/// assert_eq!("item:3:B00X%3A1".parse::<SelectionToken>().unwrap(), token);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SelectionToken {
+    pub kind: String,
+    pub index: Option<usize>,
+    pub id: Option<String>,
+    pub payload: Option<String>,
+}
+
+impl SelectionToken {
+    /// Constructs a token for an element of the given kind, e.g. `"item"`
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            index: None,
+            id: None,
+            payload: None,
+        }
+    }
+
+    /// sets the position of the element in its list
+    pub fn index(mut self, index: usize) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// sets the identifier of the element, e.g. a catalog id
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// sets arbitrary extra data carried with the element
+    pub fn payload(mut self, payload: impl Into<String>) -> Self {
+        self.payload = Some(payload.into());
+        self
+    }
+
+    /// Decodes the token of a `Display.ElementSelected` request, or returns `None` for other requests
+    pub fn from_request(req: &RequestEnvelope) -> Option<Result<Self, SelectionTokenError>> {
+        match req.request.request_type {
+            RequestType::DisplayElementSelected => req.request.token.as_ref().map(|t| t.parse()),
+            _ => None,
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('%', "%25").replace(':', "%3A")
+}
+
+fn encode(s: Option<&str>) -> String {
+    match s {
+        None => String::new(),
+        Some("") => String::from("%"),
+        Some(s) => escape(s),
+    }
+}
+
+fn decode(s: &str) -> Result<String, SelectionTokenError> {
+    match s {
+        "%" => Ok(String::new()),
+        s => unescape(s),
+    }
+}
+
+fn unescape(s: &str) -> Result<String, SelectionTokenError> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let code: String = chars.by_ref().take(2).collect();
+        match code.as_str() {
+            "25" => out.push('%'),
+            "3A" | "3a" => out.push(':'),
+            _ => return Err(SelectionTokenError::InvalidEscape(format!("%{}", code))),
+        }
+    }
+    Ok(out)
+}
+
+impl Display for SelectionToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![
+            escape(&self.kind),
+            self.index.map(|i| i.to_string()).unwrap_or_default(),
+            encode(self.id.as_deref()),
+            encode(self.payload.as_deref()),
+        ];
+        while parts.len() > 1 && parts.last().is_some_and(String::is_empty) {
+            parts.pop();
+        }
+        write!(f, "{}", parts.join(":"))
+    }
+}
+
+impl FromStr for SelectionToken {
+    type Err = SelectionTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() > 4 {
+            return Err(SelectionTokenError::TooManyParts(parts.len()));
+        }
+        let kind = unescape(parts[0])?;
+        if kind.is_empty() {
+            return Err(SelectionTokenError::Empty);
+        }
+        let part = |i: usize| parts.get(i).filter(|p| !p.is_empty());
+        let index = match part(1) {
+            Some(p) => Some(p.parse().map_err(|_| SelectionTokenError::InvalidIndex(String::from(*p)))?),
+            None => None,
+        };
+        Ok(Self {
+            kind,
+            index,
+            id: part(2).map(|p| decode(p)).transpose()?,
+            payload: part(3).map(|p| decode(p)).transpose()?,
+        })
+    }
+}
+
+/// A problem found when decoding a [`SelectionToken`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectionTokenError {
+    /// the token has no kind
+    Empty,
+    /// the index is not a non-negative integer
+    InvalidIndex(String),
+    /// a component contains a percent sign which doesn't start a known escape
+    InvalidEscape(String),
+    /// the token has more than four components
+    TooManyParts(usize),
+}
+impl Display for SelectionTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "selection token has no kind"),
+            Self::InvalidIndex(s) => write!(f, "selection token index {} is not a number", s),
+            Self::InvalidEscape(s) => write!(f, "selection token contains invalid escape {}", s),
+            Self::TooManyParts(n) => write!(f, "selection token has {} components, the maximum is 4", n),
+        }
+    }
+}
+impl std::error::Error for SelectionTokenError {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn image_conversions() {
+        let card = response::Image::new().small_image_url(String::from("https://localhost/s.png"));
+        let image = Image::from(&card);
+        assert_eq!(image.sources, vec![ImageInstance::sized("https://localhost/s.png", ImageSize::Small)]);
+        let round_trip = response::Image::try_from(&image).unwrap();
+        assert_eq!(round_trip.large_image_url.unwrap(), "https://localhost/s.png");
+
+        let image = Image::new(vec![
+            ImageInstance::sized("https://localhost/xs.png", ImageSize::XSmall),
+            ImageInstance::sized("https://localhost/m.png", ImageSize::Medium),
+            ImageInstance { url: String::from("https://localhost/xl.png"), size: None, width_pixels: Some(1920), height_pixels: None },
+        ]);
+        let card = response::Image::try_from(&image).unwrap();
+        assert_eq!(card.small_image_url.unwrap(), "https://localhost/xs.png");
+        assert_eq!(card.large_image_url.unwrap(), "https://localhost/m.png");
+
+        assert_eq!(response::Image::try_from(&Image::new(Vec::new())), Err(ImageUrlError::Empty));
+    }
+
+    #[test]
+    fn sized_image_instance() {
+        let image = ImageInstance::sized("https://localhost/art.png", ImageSize::Large);
+        assert_eq!(Ok(()), image.validate());
+        assert_eq!(
+            json!({
+                "url": "https://localhost/art.png",
+                "size": "LARGE",
+                "widthPixels": 1200,
+                "heightPixels": 800
+            }),
+            serde_json::to_value(image).unwrap()
+        );
+
+        let image = ImageInstance::sized("http://localhost/art.png", ImageSize::Other(String::from("HUGE")));
+        assert_eq!(None, image.width_pixels);
+        assert!(image.validate().is_err());
+    }
+
+    #[test]
+    fn selection_token_round_trip() {
+        let token = SelectionToken::new("item").index(3);
+        assert_eq!(token.to_string(), "item:3");
+        assert_eq!("item:3".parse::<SelectionToken>().unwrap(), token);
+
+        let token = SelectionToken::new("song").id("50%:off").payload("a:b");
+        assert_eq!(token.to_string(), "song::50%25%3Aoff:a%3Ab");
+        assert_eq!(token.to_string().parse::<SelectionToken>().unwrap(), token);
+
+        let token = SelectionToken::new("song").id("").payload("");
+        assert_eq!(token.to_string(), "song::%:%");
+        assert_eq!(token.to_string().parse::<SelectionToken>().unwrap(), token);
+        let token = SelectionToken::new("song").payload("");
+        assert_eq!(token.to_string().parse::<SelectionToken>().unwrap(), token);
+
+        assert_eq!("".parse::<SelectionToken>(), Err(SelectionTokenError::Empty));
+        assert_eq!("item:x".parse::<SelectionToken>(), Err(SelectionTokenError::InvalidIndex(String::from("x"))));
+        assert_eq!("item:1:%zz".parse::<SelectionToken>(), Err(SelectionTokenError::InvalidEscape(String::from("%zz"))));
+        assert_eq!("a:1:b:c:d".parse::<SelectionToken>(), Err(SelectionTokenError::TooManyParts(5)));
+    }
+
+    #[test]
+    fn selection_token_from_request() {
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "context": { "System": {} },
+            "request": {
+                "type": "Display.ElementSelected",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US",
+                "token": "item:2"
+            }
+        })).unwrap();
+        assert_eq!(SelectionToken::from_request(&req), Some(Ok(SelectionToken::new("item").index(2))));
+    }
+}
@@ -0,0 +1,213 @@
+//! A request context for performance-sensitive skills, which keeps its heavyweight objects (viewports,
+//! extensions and the APL visual context) as raw JSON and parses each one on first access. Deserialize a
+//! [`LazyRequestEnvelope`] instead of a [`RequestEnvelope`] to opt in:
+//! ```
+//! use alexa_sdk::lazy::LazyRequestEnvelope;
+//!
+//! let body = r#"{
+//!     "version": "1.0",
+//!     "context": { "System": {}, "Viewport": { "pixelWidth": 1280, "pixelHeight": 800 } },
+//!     "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+//! }"#;
+//! let req: LazyRequestEnvelope = serde_json::from_str(body).unwrap();
+//! assert!(req.context.viewport().unwrap().is_ok());
+//! ```
+//!
+//! Raw values can only be read by the `serde_json` deserializers for strings, byte slices and readers, so
+//! lazy envelopes can't be deserialized from a [`serde_json::Value`]. Context objects other than the ones
+//! modeled by [`LazyContext`] are kept in [`LazyContext::extra`], like [`crate::request::Context::extra`].
+//! Both contexts implement [`RequestContext`], so the helpers of [`RequestEnvelope`] such as
+//! [`RequestEnvelope::user_id`] and [`RequestEnvelope::redacted`] work with lazy envelopes too.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+use crate::request::{AplVisualContext, AudioPlayer, ExperimentationContext, RequestContext, System, Viewport};
+use crate::RequestEnvelope;
+
+/// A request envelope whose context parses its heavyweight objects on demand
+pub type LazyRequestEnvelope = RequestEnvelope<LazyContext>;
+
+/// The context of a [`LazyRequestEnvelope`]. `System`, `AudioPlayer` and `Experimentation` are small and
+/// parsed eagerly.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LazyContext {
+    pub system: System,
+    pub audio_player: Option<AudioPlayer>,
+    pub experimentation: Option<ExperimentationContext>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    viewport: Option<Lazy<Viewport>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    viewports: Option<Lazy<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extensions: Option<Lazy<HashMap<String, Value>>>,
+    #[serde(rename = "Alexa.Presentation.APL", skip_serializing_if = "Option::is_none")]
+    apl: Option<Lazy<AplVisualContext>>,
+
+    /// context objects which this crate doesn't model yet, keyed by name, see [`RequestEnvelope::context_extra`]
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl LazyContext {
+    /// the viewport of the requesting device, see [`crate::request::Context::viewport`]
+    pub fn viewport(&self) -> Option<Result<&Viewport, &serde_json::Error>> {
+        self.viewport.as_ref().map(Lazy::get)
+    }
+
+    /// the `Viewports` of the requesting device, including APLT character displays
+    pub fn viewports(&self) -> Option<Result<&Vec<Value>, &serde_json::Error>> {
+        self.viewports.as_ref().map(Lazy::get)
+    }
+
+    /// the `Extensions` context object, listing the APL extensions available on the device
+    pub fn extensions(&self) -> Option<Result<&HashMap<String, Value>, &serde_json::Error>> {
+        self.extensions.as_ref().map(Lazy::get)
+    }
+
+    /// the APL visual context, see [`crate::request::Context::apl`]
+    pub fn apl(&self) -> Option<Result<&AplVisualContext, &serde_json::Error>> {
+        self.apl.as_ref().map(Lazy::get)
+    }
+}
+
+impl RequestContext for LazyContext {
+    fn system(&self) -> &System {
+        &self.system
+    }
+
+    fn system_mut(&mut self) -> &mut System {
+        &mut self.system
+    }
+
+    fn experimentation(&self) -> Option<&ExperimentationContext> {
+        self.experimentation.as_ref()
+    }
+
+    fn extra(&self) -> &HashMap<String, Value> {
+        &self.extra
+    }
+
+    /// parses the APL visual context, treating a context which fails to parse as absent
+    fn apl_token(&self) -> Option<&str> {
+        self.apl()?.ok()?.token.as_deref()
+    }
+}
+
+/// A JSON value which is kept as raw text until it is first read, then parsed once
+pub struct Lazy<T> {
+    raw: Box<RawValue>,
+    parsed: OnceLock<Result<T, serde_json::Error>>,
+}
+
+impl<T: DeserializeOwned> Lazy<T> {
+    /// parses the value on the first call, returning the same result on later calls
+    pub fn get(&self) -> Result<&T, &serde_json::Error> {
+        self.parsed.get_or_init(|| serde_json::from_str(self.raw.get())).as_ref()
+    }
+}
+
+impl<T> Lazy<T> {
+    /// the unparsed JSON text of the value
+    pub fn raw(&self) -> &str {
+        self.raw.get()
+    }
+}
+
+impl<T> From<Box<RawValue>> for Lazy<T> {
+    fn from(raw: Box<RawValue>) -> Self {
+        Self { raw, parsed: OnceLock::new() }
+    }
+}
+
+impl<T> Clone for Lazy<T> {
+    fn clone(&self) -> Self {
+        Self::from(self.raw.clone())
+    }
+}
+
+impl<T> PartialEq for Lazy<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw() == other.raw()
+    }
+}
+
+impl<T> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Lazy").field(&self.raw()).finish()
+    }
+}
+
+impl<T> Serialize for Lazy<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.raw.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Lazy<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Box::<RawValue>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn lazy_context() {
+        let body = json!({
+            "version": "1.0",
+            "context": {
+                "System": {
+                    "apiAccessToken": "TOKEN",
+                    "device": { "deviceId": "amzn1.ask.device.DEVICE", "supportedInterfaces": { "Alexa.Presentation.APL": {} } },
+                    "user": { "userId": "amzn1.ask.account.USER" }
+                },
+                "Experimentation": { "activeExperiments": [{ "id": "greeting", "treatmentId": "T1" }] },
+                "Geolocation": { "timestamp": "2019-02-03T10:05:01Z" },
+                "Viewport": { "pixelWidth": "wide" },
+                "Viewports": [{ "type": "APL", "id": "main" }],
+                "Extensions": { "available": { "aplext:backstack:10": {} } },
+                "Alexa.Presentation.APL": { "token": "doc", "componentsVisibleOnScreen": [] }
+            },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z"
+            }
+        }).to_string();
+        let req: LazyRequestEnvelope = serde_json::from_str(&body).unwrap();
+        assert_eq!(req.request.request_id, "amzn1.echo-api.request.id");
+        assert_eq!(req.context.system.device.as_ref().unwrap().device_id, "amzn1.ask.device.DEVICE");
+        assert_eq!(req.context.apl().unwrap().unwrap().token.as_deref(), Some("doc"));
+        assert_eq!(req.context.viewports().unwrap().unwrap()[0]["id"], "main");
+        assert!(req.context.extensions().unwrap().unwrap().contains_key("available"));
+        assert!(req.context.viewport().unwrap().is_err());
+
+        assert_eq!(req.user_id().unwrap(), "amzn1.ask.account.USER");
+        assert!(req.supports_interface("Alexa.Presentation.APL"));
+        assert_eq!(req.experiment_treatment("greeting"), Some("T1"));
+        assert!(req.context_extra::<Value>("Geolocation").unwrap().is_ok());
+        #[cfg(feature = "apl")]
+        assert_eq!(crate::apl::AplToken::on_screen(&req).unwrap().as_str(), "doc");
+        assert_eq!(req.redacted().device_id().unwrap(), "amzn1.ask.device.**VICE");
+        let apis = crate::services::AlexaApis::new().for_request(&req);
+        assert!(apis.settings().time_zone().unwrap().url.contains("amzn1.ask.device.DEVICE"));
+
+        let round_trip: Value = serde_json::from_str(&serde_json::to_string(&req).unwrap()).unwrap();
+        assert_eq!(round_trip["context"]["Alexa.Presentation.APL"]["token"], "doc");
+        assert_eq!(round_trip["context"]["Viewport"]["pixelWidth"], "wide");
+        assert_eq!(round_trip["context"]["Geolocation"]["timestamp"], "2019-02-03T10:05:01Z");
+        assert_eq!(req.clone(), req);
+    }
+}
@@ -31,17 +31,17 @@
 //! ```rust
 //! use lambda_runtime::{service_fn, Error, LambdaEvent};
 //! use alexa_sdk::{RequestEnvelope, ResponseEnvelope};
-//! use alexa_sdk::request::{IntentType, Locale, Language, Region};
+//! use alexa_sdk::request::{IntentType, Language, Region};
 //!
 //! fn handle_help(_req: &RequestEnvelope) -> Result<ResponseEnvelope,Error> {
 //!     Ok(ResponseEnvelope::simple("hello", "to say hello, tell me: say hello to someone"))
 //! }
 //!
 //! fn handle_hello(req: &RequestEnvelope) -> Result<ResponseEnvelope,Error> {
-//!     let res = match req.request.locale.parts() {
-//!         (&Language::English, Some(&Region::Australia)) => ResponseEnvelope::simple("hello", "G'day mate"),
-//!         (&Language::German, _) => ResponseEnvelope::simple("hello", "Hallo Welt"),
-//!         (&Language::Japanese, _) => ResponseEnvelope::simple("hello", "こんにちは世界"),
+//!     let res = match req.locale().map(|l| l.parts()) {
+//!         Some((&Language::English, Some(&Region::Australia))) => ResponseEnvelope::simple("hello", "G'day mate"),
+//!         Some((&Language::German, _)) => ResponseEnvelope::simple("hello", "Hallo Welt"),
+//!         Some((&Language::Japanese, _)) => ResponseEnvelope::simple("hello", "こんにちは世界"),
 //!         _ => if let Some(ref s) = req.slot_value("name") {
 //!             ResponseEnvelope::simple("hello", (String::from("hello ") + s).as_str())
 //!         } else {
@@ -74,15 +74,46 @@
 //! # fn main() {}
 //! ```
 
+pub mod authorization;
+pub mod persistence;
 pub mod request;
 pub mod response;
+pub mod services;
+pub mod ssml;
+
+#[cfg(feature = "advertisement")]
+pub mod advertisement;
+
+#[cfg(feature = "apl")]
+pub mod apl;
 
 #[cfg(feature = "audioplayer")]
 pub mod audioplayer;
 
+#[cfg(feature = "compat-0x")]
+pub mod compat;
+
+#[cfg(feature = "dev")]
+pub mod dev;
+
+#[cfg(feature = "dialog")]
+pub mod dialog;
+
 #[cfg(feature = "display")]
 pub mod display;
 
+#[cfg(feature = "lazy-context")]
+pub mod lazy;
+
+#[cfg(feature = "manifest")]
+pub mod manifest;
+
+#[cfg(feature = "monetization")]
+pub mod monetization;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 pub use self::request::RequestEnvelope;
 pub use self::response::ResponseEnvelope;
 
@@ -92,6 +123,9 @@ pub use self::response::ResponseEnvelope;
 /// 
 /// - All have an extra variant called "Other" which contains any unrecognized value.
 /// - All can serialize to and from any string literal without errors.
+/// - All implement `Eq` and `Hash`, so they can be used as `HashMap` keys, e.g. to register handlers by intent.
+///   Note that `Other` values are compared by their string, so construct them by deserializing, or with `From`
+///   where it is implemented, rather than directly, to avoid an `Other` which duplicates a known variant.
 /// 
 /// There are three supported ways to declare an enum type with this macro:
 /// ## 1. Simple declarations
@@ -142,14 +176,40 @@ pub use self::response::ResponseEnvelope;
 /// assert_eq!("\"en\"", serde_json::to_string(&Language::English).unwrap());
 /// ```
 /// 
+/// ## Evolving enums
+/// 
+/// Values which Amazon adds to the spec deserialize to `Other` until the enum declares them. Declaring a new
+/// variant then moves those values out of `Other`, and a `match` without a wildcard arm stops compiling.
+/// Attributes written before the enum name, including doc comments, are applied to the enum. Mark an enum
+/// `#[non_exhaustive]` so that code in other crates must have a wildcard arm, and adding variants is not a
+/// breaking change:
+/// ```
+/// use alexa_sdk::declare_api_enum;
+/// 
+/// declare_api_enum! {
+///     /// The shape of a device screen
+///     #[non_exhaustive]
+///     Shape => "SCREAMING_SNAKE_CASE" {
+///         Rectangle,
+///         Round
+///     }
+/// };
+/// 
+/// assert_eq!(Shape::Round, serde_json::from_str("\"ROUND\"").unwrap());
+/// ```
+/// 
+/// Matching on `Other` with a string, e.g. `Shape::Other(s) if s == "OVAL"`, silently stops matching when
+/// the value gets its own variant. Prefer converting the known variants, and keep `Other` for logging.
+/// 
 #[macro_export]
 macro_rules! declare_api_enum {
-    ($rust_name:ident { $( $known_value:ident ),* }) => {
-        declare_api_enum!{ $rust_name => "PascalCase" { $($known_value),* } }
+    ($(#[$meta:meta])* $rust_name:ident { $( $known_value:ident ),* }) => {
+        declare_api_enum!{ $(#[$meta])* $rust_name => "PascalCase" { $($known_value),* } }
     };
 
-    ($rust_name:ident => $convention:literal { $( $known_value:ident ),* }) => {
-        #[derive(::serde::Serialize, ::serde::Deserialize, Clone, Debug, PartialEq)]
+    ($(#[$meta:meta])* $rust_name:ident => $convention:literal { $( $known_value:ident ),* }) => {
+        $(#[$meta])*
+        #[derive(::serde::Serialize, ::serde::Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
         #[serde(rename_all = $convention)]
         pub enum $rust_name {
             $(
@@ -161,8 +221,9 @@ macro_rules! declare_api_enum {
         }
     };
 
-    ($rust_name:ident { $( $known_value:ident => $text:literal ),* }) => {
-        #[derive(Clone, Debug, PartialEq)]
+    ($(#[$meta:meta])* $rust_name:ident { $( $known_value:ident => $text:literal ),* }) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
         pub enum $rust_name {
             $(
                 $known_value
@@ -186,7 +247,7 @@ macro_rules! declare_api_enum {
                     $(
                         $text => Self::$known_value,
                     )*
-                    s @ _ => Self::Other(s.to_string()),
+                    s => Self::Other(s.to_string()),
                 }
             }
         }
@@ -234,6 +295,35 @@ mod tests {
         assert_eq!(CardType::Other("FooBar".into()), serde_json::from_str("\"FooBar\"").unwrap());
     }
 
+    #[test]
+    fn enum_hash_keys() {
+        use std::collections::HashMap;
+        use crate::request::{IntentType, RequestType};
+
+        let mut handlers = HashMap::new();
+        handlers.insert(IntentType::Help, "help");
+        handlers.insert(IntentType::Other(String::from("OrderPizza")), "order");
+        assert_eq!(handlers[&IntentType::from("AMAZON.HelpIntent")], "help");
+        assert_eq!(handlers[&IntentType::from("OrderPizza")], "order");
+
+        let requests: std::collections::HashSet<RequestType> = [RequestType::LaunchRequest, RequestType::from("LaunchRequest")].into();
+        assert_eq!(requests.len(), 1);
+        assert!(requests.contains(&RequestType::LaunchRequest));
+    }
+
+    #[test]
+    fn enum_attributes() {
+        declare_api_enum! {
+            /// documented
+            #[non_exhaustive]
+            Mapped {
+                One => "1"
+            }
+        }
+        assert_eq!(Mapped::from("1"), Mapped::One);
+        assert_eq!(Mapped::from("2").as_str(), "2");
+    }
+
     #[test]
     fn enum_serde_renames() {
         assert_eq!("\"REPLACE_ALL\"", serde_json::to_string(&PlayBehavior::ReplaceAll).unwrap());
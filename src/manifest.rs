@@ -0,0 +1,373 @@
+//! Alexa skill manifest (`skill.json`) data structures, from [the specification](https://developer.amazon.com/en-US/docs/alexa/smapi/skill-manifest.html).
+//!
+//! Only the parts of the manifest needed to deploy a custom skill are modeled: publishing information,
+//! the custom skill api (endpoints and interfaces), permissions, and privacy and compliance.
+//! Locale-keyed maps use the locale string (e.g. `"en-US"`) as their key.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::declare_api_enum;
+
+/// The top-level `skill.json` document
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillManifestEnvelope {
+    pub manifest: SkillManifest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillManifest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_version: Option<String>,
+
+    pub publishing_information: PublishingInformation,
+    pub apis: Apis,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<Permission>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy_and_compliance: Option<PrivacyAndCompliance>,
+}
+
+impl SkillManifest {
+    /// Checks this manifest for problems which would cause it to be rejected at deployment time,
+    /// returning every problem found.
+    pub fn validate(&self) -> Result<(), Vec<ManifestError>> {
+        let mut errors = Vec::new();
+
+        let locales = &self.publishing_information.locales;
+        if locales.is_empty() {
+            errors.push(ManifestError::MissingLocales);
+        }
+        for (locale, info) in locales {
+            if info.name.trim().is_empty() {
+                errors.push(ManifestError::MissingSkillName(locale.clone()));
+            }
+        }
+
+        match &self.apis.custom {
+            Some(custom) => {
+                if let Some(endpoint) = &custom.endpoint {
+                    if endpoint.uri.trim().is_empty() {
+                        errors.push(ManifestError::EmptyEndpointUri);
+                    }
+                }
+            }
+            None => errors.push(ManifestError::MissingCustomApi),
+        }
+
+        if let Some(privacy) = &self.privacy_and_compliance {
+            if let Some(privacy_locales) = &privacy.locales {
+                for locale in privacy_locales.keys() {
+                    if !locales.contains_key(locale) {
+                        errors.push(ManifestError::UnpublishedLocale(locale.clone()));
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// A problem found by [`SkillManifest::validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManifestError {
+    /// `publishingInformation.locales` is empty
+    MissingLocales,
+    /// the publishing information for this locale has no skill name
+    MissingSkillName(String),
+    /// `apis.custom` is missing
+    MissingCustomApi,
+    /// `apis.custom.endpoint.uri` is empty
+    EmptyEndpointUri,
+    /// `privacyAndCompliance.locales` contains a locale with no publishing information
+    UnpublishedLocale(String),
+}
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingLocales => write!(f, "publishingInformation.locales must contain at least one locale"),
+            Self::MissingSkillName(l) => write!(f, "publishingInformation.locales.{} has no name", l),
+            Self::MissingCustomApi => write!(f, "apis.custom is required for a custom skill"),
+            Self::EmptyEndpointUri => write!(f, "apis.custom.endpoint.uri is empty"),
+            Self::UnpublishedLocale(l) => write!(f, "privacyAndCompliance.locales.{} has no matching publishing information", l),
+        }
+    }
+}
+impl std::error::Error for ManifestError {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishingInformation {
+    pub locales: HashMap<String, LocalePublishingInformation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_available_worldwide: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distribution_countries: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distribution_mode: Option<DistributionMode>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub testing_instructions: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalePublishingInformation {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example_phrases: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub small_icon_uri: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large_icon_uri: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Apis {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom: Option<CustomApi>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomApi {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<Endpoint>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regions: Option<HashMap<String, RegionalEndpoint>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interfaces: Option<Vec<Interface>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint {
+    pub uri: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssl_certificate_type: Option<SslCertificateType>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionalEndpoint {
+    pub endpoint: Endpoint,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Interface {
+    #[serde(rename = "type")]
+    pub interface_type: InterfaceType,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supported_viewports: Option<Vec<serde_json::Value>>,
+}
+impl From<InterfaceType> for Interface {
+    fn from(value: InterfaceType) -> Self {
+        Self {
+            interface_type: value,
+            supported_viewports: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyAndCompliance {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allows_purchases: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uses_personal_info: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_child_directed: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_export_compliant: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains_ads: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locales: Option<HashMap<String, LocalePrivacyAndCompliance>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalePrivacyAndCompliance {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy_policy_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terms_of_use_url: Option<String>,
+}
+
+declare_api_enum! {
+    DistributionMode => "SCREAMING_SNAKE_CASE" {
+        Public,
+        Private,
+        Internal
+    }
+}
+
+declare_api_enum! {
+    SslCertificateType {
+        SelfSigned,
+        Wildcard,
+        Trusted
+    }
+}
+
+declare_api_enum! {
+    InterfaceType => "SCREAMING_SNAKE_CASE" {
+        AudioPlayer,
+        AlexaPresentationApl,
+        AlexaPresentationHtml,
+        CanFulfillIntentRequest,
+        RenderTemplate,
+        VideoApp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn manifest_round_trip() {
+        let value = skill_json();
+        let envelope: SkillManifestEnvelope = serde_json::from_value(value.clone()).unwrap();
+        let manifest = &envelope.manifest;
+
+        assert_eq!(manifest.publishing_information.locales["en-US"].name, "Hello World");
+        assert_eq!(manifest.publishing_information.distribution_mode, Some(DistributionMode::Public));
+        let custom = manifest.apis.custom.as_ref().unwrap();
+        assert_eq!(custom.interfaces.as_ref().unwrap()[0].interface_type, InterfaceType::AudioPlayer);
+        assert_eq!(custom.endpoint.as_ref().unwrap().ssl_certificate_type, Some(SslCertificateType::Wildcard));
+        assert_eq!(Ok(()), manifest.validate());
+
+        assert_eq!(value, serde_json::to_value(&envelope).unwrap());
+    }
+
+    #[test]
+    fn manifest_validation() {
+        let mut manifest = SkillManifest {
+            manifest_version: None,
+            publishing_information: PublishingInformation::default(),
+            apis: Apis::default(),
+            permissions: None,
+            privacy_and_compliance: Some(PrivacyAndCompliance {
+                locales: Some(HashMap::from([
+                    (String::from("de-DE"), LocalePrivacyAndCompliance::default())
+                ])),
+                ..Default::default()
+            }),
+        };
+        assert_eq!(
+            Err(vec![
+                ManifestError::MissingLocales,
+                ManifestError::MissingCustomApi,
+                ManifestError::UnpublishedLocale(String::from("de-DE")),
+            ]),
+            manifest.validate()
+        );
+
+        manifest.publishing_information.locales.insert(String::from("de-DE"), LocalePublishingInformation::default());
+        manifest.apis.custom = Some(CustomApi {
+            interfaces: Some(vec![InterfaceType::AlexaPresentationApl.into()]),
+            ..Default::default()
+        });
+        assert_eq!(Err(vec![ManifestError::MissingSkillName(String::from("de-DE"))]), manifest.validate());
+    }
+
+    fn skill_json() -> serde_json::Value {
+        json!({
+            "manifest": {
+                "manifestVersion": "1.0",
+                "publishingInformation": {
+                    "locales": {
+                        "en-US": {
+                            "name": "Hello World",
+                            "summary": "Says hello",
+                            "description": "A skill which says hello",
+                            "examplePhrases": ["Alexa, open hello world"],
+                            "keywords": ["hello"]
+                        }
+                    },
+                    "isAvailableWorldwide": true,
+                    "distributionMode": "PUBLIC",
+                    "category": "NOVELTY",
+                    "testingInstructions": "Say hello"
+                },
+                "apis": {
+                    "custom": {
+                        "endpoint": {
+                            "uri": "arn:aws:lambda:us-east-1:123456789012:function:hello",
+                            "sslCertificateType": "Wildcard"
+                        },
+                        "interfaces": [
+                            { "type": "AUDIO_PLAYER" }
+                        ]
+                    }
+                },
+                "permissions": [
+                    { "name": "alexa::profile:given_name:read" }
+                ],
+                "privacyAndCompliance": {
+                    "allowsPurchases": false,
+                    "usesPersonalInfo": false,
+                    "isChildDirected": false,
+                    "isExportCompliant": true,
+                    "containsAds": false,
+                    "locales": {
+                        "en-US": {
+                            "privacyPolicyUrl": "https://example.com/privacy"
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,270 @@
+//! Alexa SDK in-skill purchasing datatypes, from [the specification](https://developer.amazon.com/en-US/docs/alexa/in-skill-purchase/add-isps-to-a-skill.html).
+//!
+//! A purchase flow starts with [`crate::services::MonetizationApi::in_skill_products`], whose reply parses as
+//! [`InSkillProducts`]. The skill hands the user over to Alexa's purchase flow with a `Connections.SendRequest`
+//! directive, e.g. from [`InSkillProduct::upsell`], in a response which ends the session. Alexa then sends a
+//! `Connections.Response` request, whose outcome parses as a [`PurchaseResponse`]:
+//! ```
+//! # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+//! #     "version": "1.0",
+//! #     "context": { "System": {} },
+//! #     "request": { "type": "Connections.Response", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z",
+//! #         "name": "Upsell", "status": { "code": "200", "message": "OK" }, "token": "amzn1.adg.product.ID",
+//! #         "payload": { "purchaseResult": "ALREADY_PURCHASED", "productId": "amzn1.adg.product.ID" } }
+//! # })).unwrap();
+//! use alexa_sdk::ResponseEnvelope;
+//! use alexa_sdk::monetization::{PurchaseResponse, PurchaseResult};
+//!
+//! let res = match PurchaseResponse::from_request(&req).unwrap().unwrap().purchase_result {
+//!     PurchaseResult::Accepted | PurchaseResult::AlreadyPurchased => ResponseEnvelope::ask("Puzzles", "Let's play a premium puzzle!"),
+//!     _ => ResponseEnvelope::ask("Puzzles", "Okay, let's play a free puzzle instead."),
+//! };
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::declare_api_enum;
+use crate::request::{ConnectionsStatus, RequestEnvelope, RequestType};
+use crate::response::Directive;
+
+/// `Connections.SendRequest`, which hands the session over to an Alexa-provided task, such as a purchase.
+/// The response carrying it must end the session.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SendRequestDirective {
+    /// the task, e.g. `Buy`, `Upsell` or `Cancel`
+    pub name: String,
+    pub payload: Value,
+    /// returned in the `Connections.Response` request
+    pub token: String,
+}
+impl SendRequestDirective {
+    /// Offers the product to the user after `message`, which should describe the product and ask whether the
+    /// user wants to learn more. The product id is used as the token.
+    pub fn upsell(product_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::purchase_task("Upsell", product_id.into(), Some(message.into()))
+    }
+
+    /// Starts buying the product, after the user asked to
+    pub fn buy(product_id: impl Into<String>) -> Self {
+        Self::purchase_task("Buy", product_id.into(), None)
+    }
+
+    /// Starts cancelling a subscription or refunding a consumable, after the user asked to
+    pub fn cancel(product_id: impl Into<String>) -> Self {
+        Self::purchase_task("Cancel", product_id.into(), None)
+    }
+
+    /// Replaces the token returned in the `Connections.Response` request
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = token.into();
+        self
+    }
+
+    fn purchase_task(name: &str, product_id: String, upsell_message: Option<String>) -> Self {
+        let mut payload = json!({ "InSkillProduct": { "productId": &product_id } });
+        if let Some(message) = upsell_message {
+            payload["upsellMessage"] = json!(message);
+        }
+        Self {
+            name: String::from(name),
+            payload,
+            token: product_id,
+        }
+    }
+}
+impl From<SendRequestDirective> for Directive {
+    fn from(value: SendRequestDirective) -> Self {
+        Directive::SendRequest(value)
+    }
+}
+
+declare_api_enum! {
+    PurchaseResult => "SCREAMING_SNAKE_CASE" {
+        Accepted,
+        Declined,
+        AlreadyPurchased,
+        Error
+    }
+}
+
+/// The outcome of a purchase task, from a `Connections.Response` request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurchaseResponse {
+    /// the task, e.g. `Upsell`
+    pub name: String,
+    /// the token of the `Connections.SendRequest` directive
+    pub token: Option<String>,
+    pub status: Option<ConnectionsStatus>,
+    pub purchase_result: PurchaseResult,
+    pub product_id: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PurchasePayload {
+    purchase_result: PurchaseResult,
+    product_id: Option<String>,
+    message: Option<String>,
+}
+
+impl PurchaseResponse {
+    /// Decodes a `Connections.Response` request, or returns `None` for other requests, and for responses to
+    /// tasks which aren't purchases
+    pub fn from_request(req: &RequestEnvelope) -> Option<Result<Self, serde_json::Error>> {
+        let request = &req.request;
+        if request.request_type != RequestType::ConnectionsResponse {
+            return None;
+        }
+        let name = request.name.as_ref().filter(|n| ["Buy", "Upsell", "Cancel"].contains(&n.as_str()))?;
+        let payload = request.payload.as_ref()?;
+        Some(PurchasePayload::deserialize(payload).map(|p| Self {
+            name: name.clone(),
+            token: request.token.clone(),
+            status: request.status.clone(),
+            purchase_result: p.purchase_result,
+            product_id: p.product_id,
+            message: p.message,
+        }))
+    }
+}
+
+/// The reply of [`crate::services::MonetizationApi::in_skill_products`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InSkillProducts {
+    pub in_skill_products: Vec<InSkillProduct>,
+    pub next_token: Option<String>,
+    pub is_truncated: Option<bool>,
+}
+impl InSkillProducts {
+    /// the product with the given reference name, as set in the developer console
+    pub fn find(&self, reference_name: &str) -> Option<&InSkillProduct> {
+        self.in_skill_products.iter().find(|p| p.reference_name == reference_name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InSkillProduct {
+    pub product_id: String,
+    pub reference_name: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub product_type: ProductType,
+    pub summary: Option<String>,
+    pub entitled: Entitlement,
+    pub purchasable: Purchasability,
+}
+impl InSkillProduct {
+    pub fn is_entitled(&self) -> bool {
+        self.entitled == Entitlement::Entitled
+    }
+
+    /// Offers this product after `message`, or returns `None` if the user already owns it or can't buy it
+    pub fn upsell(&self, message: &str) -> Option<SendRequestDirective> {
+        match (&self.entitled, &self.purchasable) {
+            (Entitlement::NotEntitled, Purchasability::Purchasable) => Some(SendRequestDirective::upsell(&self.product_id, message)),
+            _ => None,
+        }
+    }
+}
+
+declare_api_enum! {
+    ProductType => "SCREAMING_SNAKE_CASE" {
+        Subscription,
+        Entitlement,
+        Consumable
+    }
+}
+
+declare_api_enum! {
+    Entitlement => "SCREAMING_SNAKE_CASE" {
+        Entitled,
+        NotEntitled
+    }
+}
+
+declare_api_enum! {
+    Purchasability => "SCREAMING_SNAKE_CASE" {
+        Purchasable,
+        NotPurchasable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn upsell() {
+        let products: InSkillProducts = serde_json::from_value(json!({
+            "inSkillProducts": [{
+                "productId": "amzn1.adg.product.ID",
+                "referenceName": "premium_puzzles",
+                "name": "Premium Puzzles",
+                "type": "ENTITLEMENT",
+                "summary": "Fifty extra puzzles",
+                "entitled": "NOT_ENTITLED",
+                "entitlementReason": "NOT_PURCHASED",
+                "purchasable": "PURCHASABLE",
+                "activeEntitlementCount": 0,
+                "purchaseMode": "TEST"
+            }],
+            "nextToken": null,
+            "isTruncated": false
+        })).unwrap();
+        let product = products.find("premium_puzzles").unwrap();
+        assert!(!product.is_entitled());
+
+        let directive: Directive = product.upsell("Want fifty extra puzzles?").unwrap().into();
+        assert_eq!(directive.interface(), None);
+        assert_eq!(serde_json::to_value(&directive).unwrap(), json!({
+            "type": "Connections.SendRequest",
+            "name": "Upsell",
+            "payload": {
+                "InSkillProduct": { "productId": "amzn1.adg.product.ID" },
+                "upsellMessage": "Want fifty extra puzzles?"
+            },
+            "token": "amzn1.adg.product.ID"
+        }));
+        assert!(serde_json::to_value(SendRequestDirective::buy("amzn1.adg.product.ID")).unwrap()["payload"].get("upsellMessage").is_none());
+
+        let mut res = crate::ResponseEnvelope::new(true);
+        res.add_directive(directive.clone());
+        assert_eq!(res.validate(), Ok(()));
+        let mut res = crate::ResponseEnvelope::new(false);
+        res.add_directive(directive);
+        assert_eq!(res.validate(), Err(vec![crate::response::ValidationError::SendRequestWithOpenSession]));
+    }
+
+    #[test]
+    fn purchase_response() {
+        let response = |name: &str, result: &str| -> RequestEnvelope { serde_json::from_value(json!({
+            "version": "1.0",
+            "context": { "System": {} },
+            "request": {
+                "type": "Connections.Response",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "name": name,
+                "status": { "code": "200", "message": "OK" },
+                "payload": { "purchaseResult": result, "productId": "amzn1.adg.product.ID", "message": "optional" },
+                "token": "correlation"
+            }
+        })).unwrap() };
+        let purchase = PurchaseResponse::from_request(&response("Buy", "DECLINED")).unwrap().unwrap();
+        assert_eq!(purchase.purchase_result, PurchaseResult::Declined);
+        assert_eq!(purchase.token.as_deref(), Some("correlation"));
+        assert_eq!(purchase.status.unwrap().code, "200");
+        assert_eq!(
+            PurchaseResponse::from_request(&response("Upsell", "ALREADY_PURCHASED")).unwrap().unwrap().purchase_result,
+            PurchaseResult::AlreadyPurchased
+        );
+        assert!(PurchaseResponse::from_request(&response("ScheduleTaxiReservation", "ACCEPTED")).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! Helpers for skills which persist state beyond a single session. This crate doesn't provide a
+//! storage adapter; these types standardize how skills derive the keys they store state under.
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+
+use crate::request::{RequestContext, RequestEnvelope, RequestType};
+use crate::ResponseEnvelope;
+
+/// Determines which identifier from a request is used as the key for persisted state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyStrategy {
+    /// Key on the Alexa account (`System.user.userId`), shared by everyone using the account.
+    #[default]
+    UserId,
+    /// Key on the recognized speaker (`System.person.personId`), falling back to the user id
+    /// when the speaker isn't recognized.
+    PersonId,
+    /// Key on the device (`System.device.deviceId`), shared by everyone using the device.
+    DeviceId,
+}
+
+impl KeyStrategy {
+    /// Returns the key to persist state under for the given request, if the request carries the required ids.
+    pub fn key<'a, C: RequestContext>(&self, req: &'a RequestEnvelope<C>) -> Option<&'a str> {
+        let id = match self {
+            KeyStrategy::UserId => req.user_id(),
+            KeyStrategy::PersonId => req.person_id().or_else(|| req.user_id()),
+            KeyStrategy::DeviceId => req.device_id(),
+        };
+        id.map(String::as_str)
+    }
+}
+
+/// A persisted flag recording which keys have launched the skill before, implemented by the skill over its
+/// own storage. The `HashSet` implementation keeps the flags in memory, e.g. for tests.
+pub trait VisitedFlag {
+    type Error;
+
+    fn has_visited(&self, key: &str) -> Result<bool, Self::Error>;
+    fn set_visited(&mut self, key: &str) -> Result<(), Self::Error>;
+}
+
+impl VisitedFlag for HashSet<String> {
+    type Error = Infallible;
+
+    fn has_visited(&self, key: &str) -> Result<bool, Self::Error> {
+        Ok(self.contains(key))
+    }
+
+    fn set_visited(&mut self, key: &str) -> Result<(), Self::Error> {
+        self.insert(String::from(key));
+        Ok(())
+    }
+}
+
+/// The "welcome" / "welcome back" pattern for `LaunchRequest`s: greets first-time users with `first_time`
+/// and returning users with `returning`, keeping the session open for their answer.
+/// ```
+/// use std::collections::HashSet;
+/// use alexa_sdk::persistence::{KeyStrategy, LaunchGreeting};
+/// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+/// #     "version": "1.0",
+/// #     "context": { "System": { "user": { "userId": "amzn1.ask.account.USER" } } },
+/// #     "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+/// # })).unwrap();
+///
+/// let greeting = LaunchGreeting {
+///     title: "Pizza",
+///     first_time: "Welcome to Pizza! You can order a pizza, or ask for help.",
+///     returning: "Welcome back! What would you like to order?",
+///     key_strategy: KeyStrategy::UserId,
+/// };
+/// let mut visited = HashSet::new();
+/// let first = greeting.respond(&req, &mut visited).unwrap().unwrap();
+/// assert_eq!(first.response.card.unwrap().content.unwrap(), "Welcome to Pizza! You can order a pizza, or ask for help.");
+/// let second = greeting.respond(&req, &mut visited).unwrap().unwrap();
+/// assert_eq!(second.response.card.unwrap().content.unwrap(), "Welcome back! What would you like to order?");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchGreeting<'a> {
+    pub title: &'a str,
+    pub first_time: &'a str,
+    pub returning: &'a str,
+    pub key_strategy: KeyStrategy,
+}
+
+impl LaunchGreeting<'_> {
+    /// Greets the user of a `LaunchRequest` and records their visit, or returns `None` for other requests.
+    /// Requests without the id required by the key strategy are greeted as first-time users, and not recorded.
+    pub fn respond<C: RequestContext, F: VisitedFlag>(&self, req: &RequestEnvelope<C>, flags: &mut F) -> Option<Result<ResponseEnvelope, F::Error>> {
+        if req.request.request_type != RequestType::LaunchRequest {
+            return None;
+        }
+        let returning = match self.key_strategy.key(req) {
+            None => Ok(false),
+            Some(key) => flags.has_visited(key).and_then(|visited| {
+                if !visited {
+                    flags.set_visited(key)?;
+                }
+                Ok(visited)
+            }),
+        };
+        Some(returning.map(|returning| {
+            ResponseEnvelope::ask(self.title, if returning { self.returning } else { self.first_time })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn key_strategies() {
+        let mut value = json!({
+            "version": "1.0",
+            "context": {
+                "System": {
+                    "user": { "userId": "amzn1.ask.account.USER" },
+                    "device": { "deviceId": "amzn1.ask.device.DEVICE" }
+                }
+            },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US"
+            }
+        });
+        let req: RequestEnvelope = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(KeyStrategy::UserId.key(&req), Some("amzn1.ask.account.USER"));
+        assert_eq!(KeyStrategy::PersonId.key(&req), Some("amzn1.ask.account.USER"));
+        assert_eq!(KeyStrategy::DeviceId.key(&req), Some("amzn1.ask.device.DEVICE"));
+
+        value["context"]["System"]["person"] = json!({ "personId": "amzn1.ask.person.PERSON" });
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert_eq!(KeyStrategy::PersonId.key(&req), Some("amzn1.ask.person.PERSON"));
+    }
+
+    #[test]
+    fn launch_greeting() {
+        let launch = |person: &str| -> RequestEnvelope { serde_json::from_value(json!({
+            "version": "1.0",
+            "context": {
+                "System": {
+                    "user": { "userId": "amzn1.ask.account.USER" },
+                    "person": { "personId": person }
+                }
+            },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z"
+            }
+        })).unwrap() };
+        let greeting = LaunchGreeting {
+            title: "title",
+            first_time: "welcome",
+            returning: "welcome back",
+            key_strategy: KeyStrategy::PersonId,
+        };
+        let text = |res: ResponseEnvelope| res.response.output_speech.unwrap().text.unwrap();
+
+        let mut visited = HashSet::new();
+        assert_eq!(text(greeting.respond(&launch("amzn1.ask.person.A"), &mut visited).unwrap().unwrap()), "welcome");
+        assert_eq!(text(greeting.respond(&launch("amzn1.ask.person.A"), &mut visited).unwrap().unwrap()), "welcome back");
+        let res = greeting.respond(&launch("amzn1.ask.person.B"), &mut visited).unwrap().unwrap();
+        assert_eq!(res.response.should_end_session, Some(false));
+        assert_eq!(text(res), "welcome");
+
+        let mut intent = serde_json::to_value(launch("amzn1.ask.person.A")).unwrap();
+        intent["request"]["type"] = json!("IntentRequest");
+        assert!(greeting.respond(&serde_json::from_value::<RequestEnvelope>(intent).unwrap(), &mut visited).is_none());
+    }
+}
@@ -1,9 +1,10 @@
 use serde::de::Visitor;
 use serde::{Deserialize, Serialize};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::From;
 use std::fmt::Display;
+use std::time::Duration;
 
 use crate::declare_api_enum;
 
@@ -24,15 +25,18 @@ macro_rules! locale {
 }
 
 /// Request struct corresponding to the [Alexa spec](https://developer.amazon.com/docs/custom-skills/request-and-response-json-reference.html#request-body-parameters)
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct RequestEnvelope {
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestEnvelope<C = Context> {
     pub version: String,
     pub session: Option<Session>,
     pub request: Request,
-    pub context: Context,
+    /// empty for events which Alexa sends without a context, such as skill messages.
+    /// With the `lazy-context` feature, `lazy::LazyContext` parses its largest objects on demand instead.
+    #[serde(default)]
+    pub context: C,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Session {
     pub new: bool,
@@ -42,122 +46,1158 @@ pub struct Session {
     pub user: User,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Application {
     pub application_id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct User {
     pub user_id: String,
     pub access_token: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl User {
+    fn redact(&mut self) {
+        self.user_id = mask_id(&self.user_id);
+        redact(&mut self.access_token);
+    }
+}
+
+/// The replacement for secrets removed by [`RequestEnvelope::redacted`]
+pub const REDACTED: &str = "<redacted>";
+
+fn redact(secret: &mut Option<String>) {
+    if let Some(s) = secret.as_mut() {
+        *s = String::from(REDACTED);
+    }
+}
+
+pub(crate) fn mask_id(id: &str) -> String {
+    let (prefix, rest) = id.rsplit_once('.').map(|(p, r)| (&id[..=p.len()], r)).unwrap_or(("", id));
+    let keep = rest.chars().count().saturating_sub(4);
+    let suffix: String = rest.chars().skip(keep).collect();
+    format!("{}{}{}", prefix, "*".repeat(keep), suffix)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Device {
     pub device_id: String,
+    pub supported_interfaces: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl Device {
+    /// returns true if the device declares support for the named interface, e.g. `"AudioPlayer"`
+    /// or `"Alexa.Presentation.APL"`
+    pub fn supports(&self, interface: &str) -> bool {
+        self.supported_interfaces.as_ref().is_some_and(|i| i.contains_key(interface))
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Request {
     #[serde(rename = "type")]
     pub request_type: RequestType,
     pub request_id: String,
     pub timestamp: String,
-    pub locale: Locale,
+    /// absent from some out-of-session requests, such as skill events; see [`RequestEnvelope::locale`]
+    pub locale: Option<Locale>,
     pub intent: Option<Intent>,
-    pub reason: Option<String>,
+    pub token: Option<String>,
+    pub offset_in_milliseconds: Option<Offset>,
+    pub reason: Option<SessionEndedReason>,
+    pub error: Option<SessionEndedError>,
     pub dialog_state: Option<String>,
+    /// sent with `Alexa.Presentation.APL.RuntimeError`, see [`RequestEnvelope::apl_runtime_errors`]
+    pub errors: Option<Vec<AplRuntimeError>>,
+    /// the payload of skill events, such as `Alexa.Authorization.Grant`
+    pub body: Option<serde_json::Value>,
+    /// sent with `Alexa.Presentation.APL.UserEvent`, see [`RequestEnvelope::user_event`]
+    pub arguments: Option<Vec<serde_json::Value>>,
+    pub source: Option<UserEventSource>,
+    pub components: Option<HashMap<String, serde_json::Value>>,
+    /// sent with `Alexa.Presentation.APL.LoadTokenListData`, with `token`
+    pub list_id: Option<String>,
+    pub correlation_token: Option<String>,
+    pub page_token: Option<String>,
+    pub count: Option<u32>,
+    /// sent with `Connections.Response`, with `token`: the name of the request, e.g. `Upsell`
+    pub name: Option<String>,
+    pub status: Option<ConnectionsStatus>,
+    pub payload: Option<serde_json::Value>,
+}
+
+/// The outcome of a `Connections.SendRequest` directive, as reported by the `Connections.Response` request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionsStatus {
+    /// an HTTP status code, e.g. `"200"`
+    pub code: String,
+    pub message: Option<String>,
+}
+
+/// A request envelope together with the exact bytes it was parsed from, which are needed to verify the
+/// request signature, see [`RequestEnvelope::from_slice`]. Dereferences to the [`RequestEnvelope`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawRequestEnvelope {
+    raw: Vec<u8>,
+    envelope: RequestEnvelope,
+}
+
+impl RawRequestEnvelope {
+    /// the bytes the request was parsed from, unmodified
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn envelope(&self) -> &RequestEnvelope {
+        &self.envelope
+    }
+
+    /// splits into the raw bytes and the parsed envelope
+    pub fn into_parts(self) -> (Vec<u8>, RequestEnvelope) {
+        (self.raw, self.envelope)
+    }
+}
+
+impl std::ops::Deref for RawRequestEnvelope {
+    type Target = RequestEnvelope;
+
+    fn deref(&self) -> &Self::Target {
+        &self.envelope
+    }
 }
 
 /// Partial mapping of Context, 
 /// see https://developer.amazon.com/en-US/docs/alexa/custom-skills/request-and-response-json-reference.html#context-object
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 #[serde(rename_all = "PascalCase")] 
 pub struct Context {
     pub system: System,
     pub audio_player: Option<AudioPlayer>,
+    pub viewport: Option<Viewport>,
+    pub experimentation: Option<ExperimentationContext>,
+    #[serde(rename = "Alexa.Presentation.APL")]
+    pub apl: Option<AplVisualContext>,
+
+    /// context objects which this crate doesn't model yet, keyed by name, see [`RequestEnvelope::context_extra`]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// The parts of a request context which the helpers of [`RequestEnvelope`] read, implemented by [`Context`]
+/// and, with the `lazy-context` feature, by `lazy::LazyContext`
+pub trait RequestContext {
+    fn system(&self) -> &System;
+    fn system_mut(&mut self) -> &mut System;
+    fn experimentation(&self) -> Option<&ExperimentationContext>;
+    /// the context objects which the context doesn't model, keyed by name
+    fn extra(&self) -> &HashMap<String, serde_json::Value>;
+    /// the token of the APL document on the screen of the device, from the `Alexa.Presentation.APL` context
+    fn apl_token(&self) -> Option<&str>;
+}
+
+impl RequestContext for Context {
+    fn system(&self) -> &System {
+        &self.system
+    }
+
+    fn system_mut(&mut self) -> &mut System {
+        &mut self.system
+    }
+
+    fn experimentation(&self) -> Option<&ExperimentationContext> {
+        self.experimentation.as_ref()
+    }
+
+    fn extra(&self) -> &HashMap<String, serde_json::Value> {
+        &self.extra
+    }
+
+    fn apl_token(&self) -> Option<&str> {
+        self.apl.as_ref()?.token.as_deref()
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct System {
     pub api_access_token: Option<String>,
+    pub api_endpoint: Option<String>,
     pub device: Option<Device>,
     pub application: Option<Application>,
+    pub user: Option<User>,
+    pub person: Option<Person>,
+}
+
+/// The state of the APL document on the screen of the device, sent when the skill's document is displayed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AplVisualContext {
+    /// the token of the document, as set by its `RenderDocument` directive
+    pub token: Option<String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub components_visible_on_screen: Vec<VisibleComponent>,
+}
+
+impl AplVisualContext {
+    /// finds a visible component by its `id`, searching the children of each component depth first
+    pub fn component(&self, id: &str) -> Option<&VisibleComponent> {
+        self.components_visible_on_screen.iter().find_map(|c| c.find(id))
+    }
+}
+
+/// A component of the displayed APL document, see [`AplVisualContext`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VisibleComponent {
+    pub uid: String,
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub component_type: Option<String>,
+    pub role: Option<String>,
+    /// the size and offset of the component on screen, e.g. `1280x800+0+0:0`
+    pub position: Option<String>,
+    /// component state, such as `focused`, `checked` or the `list` and `scrollable` positions
+    pub tags: Option<serde_json::Value>,
+    #[serde(default)]
+    pub children: Vec<VisibleComponent>,
+    pub entities: Option<Vec<serde_json::Value>>,
+}
+
+impl VisibleComponent {
+    fn find(&self, id: &str) -> Option<&Self> {
+        if self.id.as_deref() == Some(id) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|c| c.find(id))
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// The skill experiments the user is part of, see [`RequestEnvelope::experiment_treatment`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentationContext {
+    pub active_experiments: Vec<ActiveExperiment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveExperiment {
+    pub id: String,
+    pub treatment_id: String,
+}
+
+/// The recognized speaker, when the user has set up voice recognition and the skill has personalization enabled
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    pub person_id: String,
+    pub access_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioPlayer {
     pub token: Option<String>,
-    pub offset_in_milliseconds: Option<i64>, // should be non-zero positive, but Alexa has been observed to send -1 for this value.
-    pub player_activity: Option<String>,
+    pub offset_in_milliseconds: Option<Offset>,
+    pub player_activity: Option<PlayerActivity>,
+}
+
+impl AudioPlayer {
+    /// The state of playback, with the stream position for every activity other than `IDLE`.
+    /// A non-idle activity reported without a token is returned as [`PlaybackState::Unknown`].
+    pub fn state(&self) -> PlaybackState<'_> {
+        let position = self.token.as_deref().map(|token| StreamPosition {
+            token,
+            offset: self.offset_in_milliseconds.unwrap_or_default(),
+        });
+        match (self.player_activity.as_ref(), position) {
+            (Some(PlayerActivity::Idle), _) => PlaybackState::Idle,
+            (Some(PlayerActivity::Playing), Some(p)) => PlaybackState::Playing(p),
+            (Some(PlayerActivity::Paused), Some(p)) => PlaybackState::Paused(p),
+            (Some(PlayerActivity::BufferUnderrun), Some(p)) => PlaybackState::BufferUnderrun(p),
+            (Some(PlayerActivity::Finished), Some(p)) => PlaybackState::Finished(p),
+            (Some(PlayerActivity::Stopped), Some(p)) => PlaybackState::Stopped(p),
+            _ => PlaybackState::Unknown,
+        }
+    }
+}
+
+declare_api_enum! {
+    PlayerActivity => "SCREAMING_SNAKE_CASE" {
+        Idle,
+        Paused,
+        Playing,
+        BufferUnderrun,
+        Finished,
+        Stopped
+    }
+}
+
+/// The state of the `AudioPlayer` of the requesting device, see [`AudioPlayer::state`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackState<'a> {
+    /// nothing has been played, or playback was cleared
+    Idle,
+    Playing(StreamPosition<'a>),
+    Paused(StreamPosition<'a>),
+    /// playback is stalled waiting for data
+    BufferUnderrun(StreamPosition<'a>),
+    Finished(StreamPosition<'a>),
+    Stopped(StreamPosition<'a>),
+    /// the activity is missing or unrecognized, or the token is missing
+    Unknown,
+}
+
+impl<'a> PlaybackState<'a> {
+    /// the stream and offset of the player, for every state other than `Idle` and `Unknown`
+    pub fn position(&self) -> Option<StreamPosition<'a>> {
+        match *self {
+            PlaybackState::Playing(p)
+            | PlaybackState::Paused(p)
+            | PlaybackState::BufferUnderrun(p)
+            | PlaybackState::Finished(p)
+            | PlaybackState::Stopped(p) => Some(p),
+            PlaybackState::Idle | PlaybackState::Unknown => None,
+        }
+    }
+}
+
+/// The stream last played by the `AudioPlayer`, and how far into it playback got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamPosition<'a> {
+    pub token: &'a str,
+    pub offset: Offset,
+}
+
+/// The screen of the requesting device, for devices with a screen,
+/// see https://developer.amazon.com/en-US/docs/alexa/alexa-presentation-language/apl-viewport-property.html
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Viewport {
+    pub experiences: Option<Vec<ViewportExperience>>,
+    pub mode: Option<ViewportMode>,
+    pub shape: Option<ViewportShape>,
+    pub pixel_width: Option<u32>,
+    pub pixel_height: Option<u32>,
+    pub dpi: Option<u32>,
+    pub current_pixel_width: Option<u32>,
+    pub current_pixel_height: Option<u32>,
+    pub touch: Option<Vec<ViewportTouch>>,
+    pub keyboard: Option<Vec<ViewportKeyboard>>,
+    pub video: Option<ViewportVideo>,
+}
+
+impl Viewport {
+    /// Returns the current `(width, height)` of the viewport in display-independent pixels, if known
+    /// and representable as a `u32`
+    pub fn dp_size(&self) -> Option<(u32, u32)> {
+        let width = self.current_pixel_width.or(self.pixel_width)?;
+        let height = self.current_pixel_height.or(self.pixel_height)?;
+        let dpi = self.dpi.filter(|d| *d > 0)?;
+        let dp = |px: u32| u32::try_from(u64::from(px) * 160 / u64::from(dpi)).ok();
+        Some((dp(width)?, dp(height)?))
+    }
+
+    /// true if the screen accepts touch input
+    pub fn supports_touch(&self) -> bool {
+        self.touch.as_ref().is_some_and(|t| t.contains(&ViewportTouch::Single))
+    }
+
+    /// true if the device has directional keys, such as a TV remote
+    pub fn supports_direction_keys(&self) -> bool {
+        self.keyboard.as_ref().is_some_and(|k| k.contains(&ViewportKeyboard::Direction))
+    }
+
+    /// Classifies this viewport into one of the standard Alexa viewport profiles, or `None` if it matches none of them
+    pub fn profile(&self) -> Option<ViewportProfile> {
+        let (width, height) = self.dp_size()?;
+        match (self.mode.as_ref()?, self.shape.as_ref()?) {
+            (ViewportMode::Hub, ViewportShape::Round) => Some(ViewportProfile::HubRoundSmall),
+            (ViewportMode::Tv, ViewportShape::Rectangle) => Some(ViewportProfile::TvLandscapeXLarge),
+            (ViewportMode::Hub, ViewportShape::Rectangle) if height > width => Some(ViewportProfile::HubPortraitMedium),
+            (ViewportMode::Hub, ViewportShape::Rectangle) => match (width, height) {
+                (_, h) if h < 600 => Some(ViewportProfile::HubLandscapeSmall),
+                (w, _) if w < 1280 => Some(ViewportProfile::HubLandscapeMedium),
+                (w, _) if w < 1920 => Some(ViewportProfile::HubLandscapeLarge),
+                _ => Some(ViewportProfile::HubLandscapeXLarge),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The standard Alexa viewport profiles, which group devices of similar mode, shape and size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViewportProfile {
+    /// round screens, such as the Echo Spot
+    HubRoundSmall,
+    /// landscape screens less than 600dp high, such as the Echo Show 5
+    HubLandscapeSmall,
+    /// landscape screens less than 1280dp wide, such as the Echo Show 8
+    HubLandscapeMedium,
+    /// landscape screens less than 1920dp wide, such as the Echo Show 10
+    HubLandscapeLarge,
+    /// landscape screens at least 1920dp wide, such as the Echo Show 15
+    HubLandscapeXLarge,
+    /// portrait screens, such as the Echo Show 15 mounted vertically
+    HubPortraitMedium,
+    /// televisions, such as Fire TV
+    TvLandscapeXLarge,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewportExperience {
+    pub arc_minute_width: Option<u32>,
+    pub arc_minute_height: Option<u32>,
+    pub can_rotate: Option<bool>,
+    pub can_resize: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewportVideo {
+    pub codecs: Vec<String>,
+}
+
+declare_api_enum! {
+    ViewportMode => "SCREAMING_SNAKE_CASE" {
+        Auto,
+        Hub,
+        Mobile,
+        Pc,
+        Tv
+    }
+}
+
+declare_api_enum! {
+    ViewportTouch => "SCREAMING_SNAKE_CASE" {
+        Single
+    }
+}
+
+declare_api_enum! {
+    ViewportKeyboard => "SCREAMING_SNAKE_CASE" {
+        Direction
+    }
+}
+
+declare_api_enum! {
+    ViewportShape => "SCREAMING_SNAKE_CASE" {
+        Rectangle,
+        Round
+    }
+}
+
+/// A playback offset, serialized as a whole number of milliseconds.
+///
+/// Offsets should never be negative, but Alexa has been observed to send -1. Negative values
+/// deserialize as a zero offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Offset(Duration);
+
+impl Offset {
+    /// the start of the stream
+    pub const ZERO: Offset = Offset(Duration::ZERO);
+
+    /// creates an offset the given number of milliseconds into the stream
+    pub fn from_millis(millis: u64) -> Self {
+        Self(Duration::from_millis(millis))
+    }
+
+    /// the offset in whole milliseconds, as it is sent to Alexa. Sub-millisecond precision is dropped.
+    pub fn as_millis(&self) -> u64 {
+        self.0.as_millis() as u64
+    }
+
+    /// the offset as a [`Duration`] from the start of the stream
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+}
+impl From<Duration> for Offset {
+    fn from(value: Duration) -> Self {
+        Self(value)
+    }
+}
+impl From<Offset> for Duration {
+    fn from(value: Offset) -> Self {
+        value.0
+    }
+}
+impl Serialize for Offset {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: serde::Serializer {
+        serializer.serialize_u64(self.as_millis())
+    }
+}
+impl<'de> Deserialize<'de> for Offset {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: serde::Deserializer<'de> {
+
+        struct OffsetVisitor;
+        impl<'de> Visitor<'de> for OffsetVisitor {
+            type Value = Offset;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "an integer number of milliseconds")
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+                where E: serde::de::Error, {
+                Ok(Offset::from_millis(v.max(0) as u64))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+                where E: serde::de::Error, {
+                Ok(Offset::from_millis(v))
+            }
+        }
+
+        deserializer.deserialize_i64(OffsetVisitor)
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Intent {
     pub name: IntentType,
     pub confirmation_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub slots: Option<HashMap<String, Slot>>,
 }
 
 impl Intent {
+    /// Starts building an intent with the given name, e.g. for the `updatedIntent` of a dialog directive:
+    /// ```
+    /// use alexa_sdk::request::Intent;
+    ///
+    /// let intent = Intent::builder("OrderPizza")
+    ///     .slot("size", "large")
+    ///     .confirm_slot("size")
+    ///     .build();
+    ///
+    /// assert_eq!(intent.slots.unwrap()["size"].confirmation_status.as_deref(), Some("CONFIRMED"));
+    /// ```
+    pub fn builder<N: Into<IntentType>>(name: N) -> IntentBuilder {
+        IntentBuilder {
+            intent: Intent {
+                name: name.into(),
+                confirmation_status: Some(ConfirmationStatus::None.as_str().to_string()),
+                slots: None,
+            },
+        }
+    }
+
     fn get_slot(&self, name: &str) -> Option<&Slot> {
         self.slots.as_ref()?.get(name)
     }
+
+    /// the `confirmationStatus` of the intent, if Alexa sent one
+    pub fn confirmation(&self) -> Option<ConfirmationStatus> {
+        self.confirmation_status.as_deref().map(ConfirmationStatus::from)
+    }
+
+    /// returns true if the user confirmed the intent when Alexa asked them to
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmation() == Some(ConfirmationStatus::Confirmed)
+    }
+
+    /// returns true if the user denied the intent when Alexa asked them to confirm it
+    pub fn is_denied(&self) -> bool {
+        self.confirmation() == Some(ConfirmationStatus::Denied)
+    }
+
+    fn slot_mut(&mut self, name: &str) -> &mut Slot {
+        self.slots
+            .get_or_insert_with(HashMap::new)
+            .entry(String::from(name))
+            .or_insert_with(|| Slot {
+                name: String::from(name),
+                value: None,
+                confirmation_status: Some(ConfirmationStatus::None.as_str().to_string()),
+                resolutions: None,
+                slot_value: None,
+            })
+    }
+
+    /// sets the value of the named slot, unless the user already provided one
+    pub fn default_slot(&mut self, name: &str, value: &str) {
+        self.slot_mut(name).value.get_or_insert_with(|| String::from(value));
+    }
+
+    /// the slots of this intent as a [`SlotTree`], nested by the dotted parts of their names
+    pub fn slot_tree(&self) -> SlotTree<'_> {
+        let mut tree = SlotTree::default();
+        for (name, slot) in self.slots.iter().flatten() {
+            tree.insert(name, slot);
+        }
+        tree
+    }
+}
+
+/// A nested view of the slots of an intent, splitting dotted slot names such as `object.byArtist.name`
+/// into a path. Like [`serde_json::Value`], indexing with a name which doesn't exist yields an empty tree
+/// rather than panicking:
+/// ```
+/// # use alexa_sdk::request::Intent;
+/// let intent = Intent::builder("AMAZON.PlaybackAction<object@MusicCreativeWork>")
+///     .slot("object.byArtist.name", "the beatles")
+///     .build();
+/// let tree = intent.slot_tree();
+///
+/// assert_eq!(tree["object"]["byArtist"]["name"].value().unwrap(), "the beatles");
+/// assert!(tree["object"]["name"].value().is_none());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SlotTree<'a> {
+    /// the slot whose full name is the path to this node, if there is one
+    pub slot: Option<&'a Slot>,
+    pub children: BTreeMap<&'a str, SlotTree<'a>>,
+}
+
+static EMPTY_SLOT_TREE: SlotTree<'static> = SlotTree {
+    slot: None,
+    children: BTreeMap::new(),
+};
+
+impl<'a> SlotTree<'a> {
+    fn insert(&mut self, path: &'a str, slot: &'a Slot) {
+        let mut node = self;
+        for part in path.split('.') {
+            node = node.children.entry(part).or_default();
+        }
+        node.slot = Some(slot);
+    }
+
+    /// the child with the given name, if it exists
+    pub fn get(&self, name: &str) -> Option<&SlotTree<'a>> {
+        self.children.get(name)
+    }
+
+    /// the value of the slot at this node, if one was resolved
+    pub fn value(&self) -> Option<&'a String> {
+        self.slot?.value.as_ref()
+    }
+
+    /// true if there is neither a slot at this node nor any below it
+    pub fn is_empty(&self) -> bool {
+        self.slot.is_none() && self.children.is_empty()
+    }
+}
+
+impl<'a> std::ops::Index<&str> for SlotTree<'a> {
+    type Output = SlotTree<'a>;
+
+    fn index(&self, name: &str) -> &Self::Output {
+        self.children.get(name).unwrap_or(&EMPTY_SLOT_TREE)
+    }
+}
+
+/// A read-only view of a `CanFulfillIntentRequest`, see [`RequestEnvelope::can_fulfill_query`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CanFulfillQuery<'a> {
+    pub intent: &'a Intent,
+}
+
+impl<'a> CanFulfillQuery<'a> {
+    /// the name of the intent Alexa is asking about
+    pub fn intent_name(&self) -> &'a str {
+        self.intent.name.as_str()
+    }
+
+    /// the slots Alexa resolved from the utterance, with or without values
+    pub fn slots(&self) -> impl Iterator<Item = &'a Slot> {
+        self.intent.slots.iter().flat_map(|s| s.values())
+    }
+
+    /// the value of the named slot, if one was resolved
+    pub fn slot_value(&self, name: &str) -> Option<&'a String> {
+        self.intent.get_slot(name)?.value.as_ref()
+    }
+}
+
+/// Default values for slots the user didn't fill, per intent, applied to a request before it is handled:
+/// ```
+/// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+/// #     "version": "1.0",
+/// #     "context": { "System": {} },
+/// #     "request": { "type": "IntentRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z",
+/// #         "intent": { "name": "OrderPizza", "confirmationStatus": "NONE" } }
+/// # })).unwrap();
+/// use alexa_sdk::request::SlotDefaults;
+///
+/// let defaults = SlotDefaults::new()
+///     .slot("OrderPizza", "size", "medium")
+///     .slot("OrderPizza", "crust", "thin");
+///
+/// let mut req = req;
+/// defaults.apply(&mut req);
+/// assert_eq!(req.slot_value("size").unwrap(), "medium");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SlotDefaults {
+    defaults: HashMap<String, Vec<(String, String)>>,
+}
+
+impl SlotDefaults {
+    /// creates an empty set of defaults, add to it with [`SlotDefaults::slot`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// declares the default value of a slot of the named intent
+    pub fn slot(mut self, intent: &str, slot: &str, value: &str) -> Self {
+        self.defaults
+            .entry(String::from(intent))
+            .or_default()
+            .push((String::from(slot), String::from(value)));
+        self
+    }
+
+    /// fills in the declared defaults for any slots of the request intent without a value, see [`Intent::default_slot`]
+    pub fn apply(&self, req: &mut RequestEnvelope) {
+        let Some(intent) = req.request.intent.as_mut() else {
+            return;
+        };
+        for (slot, value) in self.defaults.get(intent.name.as_str()).into_iter().flatten() {
+            intent.default_slot(slot, value);
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// The APL component which raised a `UserEvent` with a `SendEvent` command
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserEventSource {
+    /// the component type, e.g. `TouchWrapper`
+    #[serde(rename = "type")]
+    pub source_type: String,
+    /// the event handler which ran the command, e.g. `Press`
+    pub handler: String,
+    pub id: Option<String>,
+    pub value: Option<serde_json::Value>,
+}
+
+/// A read-only view of an `Alexa.Presentation.APL.UserEvent`, see [`RequestEnvelope::user_event`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UserEvent<'a> {
+    pub request: &'a Request,
+}
+
+impl<'a> UserEvent<'a> {
+    /// the token of the document which raised the event
+    pub fn token(&self) -> Option<&'a str> {
+        self.request.token.as_deref()
+    }
+
+    /// the `arguments` of the `SendEvent` command
+    pub fn arguments(&self) -> &'a [serde_json::Value] {
+        self.request.arguments.as_deref().unwrap_or_default()
+    }
+
+    /// decodes the argument at `index`, or returns `None` if there are fewer arguments
+    pub fn argument<T: serde::de::DeserializeOwned>(&self, index: usize) -> Option<Result<T, serde_json::Error>> {
+        self.arguments().get(index).map(|v| T::deserialize(v))
+    }
+
+    /// the component which raised the event
+    pub fn source(&self) -> Option<&'a UserEventSource> {
+        self.request.source.as_ref()
+    }
+
+    /// the values of the components named in the `components` property of the `SendEvent` command
+    pub fn component(&self, id: &str) -> Option<&'a serde_json::Value> {
+        self.request.components.as_ref()?.get(id)
+    }
+}
+
+/// Builder for [`Intent`], see [`Intent::builder`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntentBuilder {
+    intent: Intent,
+}
+
+impl IntentBuilder {
+    /// sets the value of the named slot
+    pub fn slot(mut self, name: &str, value: &str) -> Self {
+        self.slot_mut(name).value = Some(String::from(value));
+        self
+    }
+
+    /// marks the named slot as confirmed, adding it without a value if it isn't present
+    pub fn confirm_slot(mut self, name: &str) -> Self {
+        self.slot_mut(name).confirmation_status = Some(ConfirmationStatus::Confirmed.as_str().to_string());
+        self
+    }
+
+    pub fn build(self) -> Intent {
+        self.intent
+    }
+
+    fn slot_mut(&mut self, name: &str) -> &mut Slot {
+        self.intent.slot_mut(name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Slot {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value: Option<String>,
     pub confirmation_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolutions: Option<Resolution>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot_value: Option<SlotValue>,
+}
+
+declare_api_enum! {
+    ConfirmationStatus {
+        None => "NONE",
+        Confirmed => "CONFIRMED",
+        Denied => "DENIED"
+    }
+}
+
+impl Slot {
+    /// the `confirmationStatus` of the slot value, if Alexa sent one
+    pub fn confirmation(&self) -> Option<ConfirmationStatus> {
+        self.confirmation_status.as_deref().map(ConfirmationStatus::from)
+    }
+
+    /// every value the user gave for this slot, with the entity each resolved to: the `values` of a
+    /// multi-value slot, or else the single value of the slot. Empty if the slot wasn't filled.
+    pub fn resolved_values(&self) -> Vec<ResolvedValue<'_>> {
+        match &self.slot_value {
+            Some(slot_value) => slot_value.resolved_values(),
+            None => self.value.iter()
+                .map(|spoken| ResolvedValue::new(spoken, self.resolutions.as_ref()))
+                .collect(),
+        }
+    }
+}
+
+/// The structured value of a slot, which holds a list of values when the slot is multi-value
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotValue {
+    #[serde(rename = "type")]
+    pub value_type: SlotValueType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub resolutions: Option<Resolution>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<SlotValue>>,
+}
+
+impl SlotValue {
+    fn resolved_values(&self) -> Vec<ResolvedValue<'_>> {
+        match &self.values {
+            Some(values) => values.iter().flat_map(SlotValue::resolved_values).collect(),
+            None => self.value.iter()
+                .map(|spoken| ResolvedValue::new(spoken, self.resolutions.as_ref()))
+                .collect(),
+        }
+    }
+}
+
+declare_api_enum! {
+    SlotValueType {
+        Simple,
+        List
+    }
+}
+
+/// One value the user gave for a slot, see [`Slot::resolved_values`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedValue<'a> {
+    /// the value as the user spoke it
+    pub spoken: &'a str,
+    /// the entity of the first authority which matched the value, if any
+    pub entity: Option<&'a Value>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl<'a> ResolvedValue<'a> {
+    fn new(spoken: &'a str, resolutions: Option<&'a Resolution>) -> Self {
+        Self {
+            spoken,
+            entity: resolutions.and_then(Resolution::first_match),
+        }
+    }
+
+    /// the id of the matched entity
+    pub fn id(&self) -> Option<&'a str> {
+        self.entity?.id.as_deref()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Resolution {
     pub resolutions_per_authority: Vec<ResolutionsPerAuthority>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl Resolution {
+    /// the first value of the first authority whose status is `ER_SUCCESS_MATCH`
+    pub fn first_match(&self) -> Option<&Value> {
+        self.resolutions_per_authority.iter()
+            .find(|r| r.is_match())?
+            .values.first()
+            .map(|w| &w.value)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ResolutionsPerAuthority {
     pub authority: String,
     pub status: Status,
     pub values: Vec<ValueWrapper>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl ResolutionsPerAuthority {
+    /// whether this authority matched the slot value to at least one of its values
+    pub fn is_match(&self) -> bool {
+        self.status.code == StatusCode::SuccessMatch
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Status {
-    pub code: String,
+    pub code: StatusCode,
+}
+
+declare_api_enum! {
+    StatusCode {
+        SuccessMatch => "ER_SUCCESS_MATCH",
+        SuccessNoMatch => "ER_SUCCESS_NO_MATCH",
+        ErrorTimeout => "ER_ERROR_TIMEOUT",
+        ErrorException => "ER_ERROR_EXCEPTION"
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ValueWrapper {
     pub value: Value,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Value {
     pub name: String,
-    pub id: String,
+    /// missing for values of authorities which don't assign ids, see [`Value::key`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl Value {
+    /// the id of this value, or its name for values without an id
+    pub fn key(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// The reason a slot has no value, see [`RequestEnvelope::try_slot_value`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlotError {
+    /// the request has no intent
+    NoIntent,
+    /// the intent has no slot with this name
+    NoSlot(String),
+    /// the slot is present, but the user didn't fill it
+    EmptySlot(String),
+}
+impl Display for SlotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoIntent => write!(f, "request has no intent"),
+            Self::NoSlot(name) => write!(f, "intent has no slot {}", name),
+            Self::EmptySlot(name) => write!(f, "slot {} has no value", name),
+        }
+    }
+}
+impl std::error::Error for SlotError {}
+
+/// The body of an `AlexaHouseholdListEvent.ItemsCreated`, `ItemsUpdated` or `ItemsDeleted` request, sent when
+/// the user changes a list the skill has permission to read, see [`RequestEnvelope::household_list_event`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HouseholdListEvent {
+    pub list_id: String,
+    #[serde(default)]
+    pub list_item_ids: Vec<String>,
+}
+
+/// The body of a `Reminders.*` request, sent when a reminder the skill created changes, see
+/// [`RequestEnvelope::reminder_event`]. `ReminderDeleted` carries `alert_tokens`, the other events `alert_token`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderEvent {
+    pub alert_token: Option<String>,
+    #[serde(default)]
+    pub alert_tokens: Vec<String>,
+    /// sent with `ReminderStatusChanged`
+    pub status: Option<ReminderStatus>,
+}
+
+impl ReminderEvent {
+    /// the tokens of the reminders this event is about, as used by [`crate::services::RemindersApi`]
+    pub fn tokens(&self) -> impl Iterator<Item = &str> {
+        self.alert_token.iter().chain(self.alert_tokens.iter()).map(String::as_str)
+    }
+}
+
+declare_api_enum! {
+    ReminderStatus => "SCREAMING_SNAKE_CASE" {
+        On,
+        Completed
+    }
+}
+
+/// The payload of an `Alexa.DataStore.PackageManager.*` request, sent when the user installs, removes or
+/// updates a DataStore package such as an APL widget, see [`RequestEnvelope::datastore_package_event`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStorePackageEvent {
+    pub package_id: String,
+    /// sent with `UsagesInstalled` and `UsagesRemoved`
+    pub package_version: Option<String>,
+    /// the version which failed to install, sent with `InstallationError`
+    pub version: Option<String>,
+    /// the installed version, sent with `UpdateRequest`
+    pub from_version: Option<String>,
+    /// the version to update to, sent with `UpdateRequest`
+    pub to_version: Option<String>,
+    #[serde(default)]
+    pub usages: Vec<DataStorePackageUsage>,
+    /// sent with `InstallationError`
+    pub error: Option<DataStorePackageError>,
+}
+
+/// Where a DataStore package is used, e.g. the widgets of a package on the user's devices
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStorePackageUsage {
+    #[serde(default)]
+    pub instances: Vec<DataStorePackageInstance>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStorePackageInstance {
+    pub instance_id: String,
+    pub location: Option<String>,
+}
+
+/// The reason a DataStore package couldn't be installed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStorePackageError {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    pub content: Option<serde_json::Value>,
+}
+
+/// Error details sent with a `SessionEndedRequest` whose reason is `ERROR`, and with `System.ExceptionEncountered`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEndedError {
+    #[serde(rename = "type")]
+    pub error_type: SessionEndedErrorType,
+    pub message: Option<String>,
+}
+
+/// An error reported by a device while rendering an APL document or running APL commands,
+/// sent in an `Alexa.Presentation.APL.RuntimeError` request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AplRuntimeError {
+    #[serde(rename = "type")]
+    pub error_type: AplRuntimeErrorType,
+    pub reason: Option<String>,
+    pub list_id: Option<String>,
+    pub message: Option<String>,
+}
+
+declare_api_enum! {
+    AplRuntimeErrorType => "SCREAMING_SNAKE_CASE" {
+        ListError,
+        LinkError,
+        RenderError,
+        InternalError
+    }
+}
+
+declare_api_enum! {
+    SessionEndedReason => "SCREAMING_SNAKE_CASE" {
+        UserInitiated,
+        Error,
+        ExceededMaxReprompts
+    }
+}
+
+declare_api_enum! {
+    SessionEndedErrorType => "SCREAMING_SNAKE_CASE" {
+        InvalidResponse,
+        DeviceCommunicationError,
+        InternalServiceError,
+        EndpointTimeout
+    }
+}
+
+declare_api_enum! {
+    DialogState {
+        Started => "STARTED",
+        InProgress => "IN_PROGRESS",
+        Completed => "COMPLETED"
+    }
 }
 
 declare_api_enum! {
     RequestType {
-        LaunchRequest,
-        IntentRequest,
-        SessionEndedRequest,
-        CanFulfillIntentRequest
+        LaunchRequest => "LaunchRequest",
+        IntentRequest => "IntentRequest",
+        SessionEndedRequest => "SessionEndedRequest",
+        CanFulfillIntentRequest => "CanFulfillIntentRequest",
+        AudioPlayerPlaybackStarted => "AudioPlayer.PlaybackStarted",
+        AudioPlayerPlaybackFinished => "AudioPlayer.PlaybackFinished",
+        AudioPlayerPlaybackStopped => "AudioPlayer.PlaybackStopped",
+        AudioPlayerPlaybackNearlyFinished => "AudioPlayer.PlaybackNearlyFinished",
+        AudioPlayerPlaybackFailed => "AudioPlayer.PlaybackFailed",
+        AdvertisementAdCompleted => "Alexa.Advertisement.AdCompleted",
+        AdvertisementAdNotRendered => "Alexa.Advertisement.AdNotRendered",
+        PlaybackControllerNextCommandIssued => "PlaybackController.NextCommandIssued",
+        PlaybackControllerPauseCommandIssued => "PlaybackController.PauseCommandIssued",
+        PlaybackControllerPlayCommandIssued => "PlaybackController.PlayCommandIssued",
+        PlaybackControllerPreviousCommandIssued => "PlaybackController.PreviousCommandIssued",
+        SystemExceptionEncountered => "System.ExceptionEncountered",
+        AplRuntimeError => "Alexa.Presentation.APL.RuntimeError",
+        AlexaAuthorizationGrant => "Alexa.Authorization.Grant",
+        AplUserEvent => "Alexa.Presentation.APL.UserEvent",
+        DisplayElementSelected => "Display.ElementSelected",
+        HouseholdListItemsCreated => "AlexaHouseholdListEvent.ItemsCreated",
+        HouseholdListItemsUpdated => "AlexaHouseholdListEvent.ItemsUpdated",
+        HouseholdListItemsDeleted => "AlexaHouseholdListEvent.ItemsDeleted",
+        AplLoadTokenListData => "Alexa.Presentation.APL.LoadTokenListData",
+        DataStoreUsagesInstalled => "Alexa.DataStore.PackageManager.UsagesInstalled",
+        DataStoreUsagesRemoved => "Alexa.DataStore.PackageManager.UsagesRemoved",
+        DataStoreUpdateRequest => "Alexa.DataStore.PackageManager.UpdateRequest",
+        DataStoreInstallationError => "Alexa.DataStore.PackageManager.InstallationError",
+        DataStoreError => "Alexa.DataStore.Error",
+        ReminderCreated => "Reminders.ReminderCreated",
+        ReminderUpdated => "Reminders.ReminderUpdated",
+        ReminderDeleted => "Reminders.ReminderDeleted",
+        ReminderStarted => "Reminders.ReminderStarted",
+        ReminderStatusChanged => "Reminders.ReminderStatusChanged",
+        ConnectionsResponse => "Connections.Response"
     }
 }
 
@@ -168,14 +1208,23 @@ declare_api_enum! {
         Fallback => "AMAZON.FallbackIntent",
         LoopOff => "AMAZON.LoopOffIntent",
         LoopOn => "AMAZON.LoopOnIntent",
+        More => "AMAZON.MoreIntent",
         NavigateHome => "AMAZON.NavigateHomeIntent",
+        NavigateSettings => "AMAZON.NavigateSettingsIntent",
         Next => "AMAZON.NextIntent",
         No => "AMAZON.NoIntent",
+        PageDown => "AMAZON.PageDownIntent",
+        PageUp => "AMAZON.PageUpIntent",
         Pause => "AMAZON.PauseIntent",
         Previous => "AMAZON.PreviousIntent",
         Repeat => "AMAZON.RepeatIntent",
         Resume => "AMAZON.ResumeIntent",
+        ScrollDown => "AMAZON.ScrollDownIntent",
+        ScrollLeft => "AMAZON.ScrollLeftIntent",
+        ScrollRight => "AMAZON.ScrollRightIntent",
+        ScrollUp => "AMAZON.ScrollUpIntent",
         Select => "AMAZON.SelectIntent",
+        SendToPhone => "AMAZON.SendToPhoneIntent",
         ShuffleOff => "AMAZON.ShuffleOffIntent",
         ShuffleOn => "AMAZON.ShuffleOnIntent",
         StartOver => "AMAZON.StartOverIntent",
@@ -184,17 +1233,48 @@ declare_api_enum! {
     }
 }
 
-/// Alexa standard locales
+impl IntentType {
+    /// `true` for Amazon's built-in intents, including those of the built-in intent library which this
+    /// crate doesn't model, such as `AMAZON.SearchAction<object@WeatherForecast>`
+    pub fn is_builtin(&self) -> bool {
+        match self {
+            Self::Other(name) => name.starts_with("AMAZON."),
+            _ => true,
+        }
+    }
+}
+
+/// Alexa standard locales. Languages and regions which Amazon adds in the future are
+/// represented by the `Other` variants of [`Language`] and [`Region`].
 #[derive(Debug, PartialEq, Clone)]
 pub struct Locale {
     pub language: Language,
     pub region: Option<Region>,
 }
 impl Locale {
+    /// The last entry of every [`Locale::fallback_chain`]
+    pub const DEFAULT_KEY: &'static str = "default";
+
     /// Return a tuple of references to this Locale's fields, for convenience in pattern matching.
     pub fn parts(&self) -> (&Language, Option<&Region>) {
         (&self.language, self.region.as_ref())
     }
+
+    /// Returns the keys to try, most specific first, when looking up localized content for this locale:
+    /// ```
+    /// use alexa_sdk::locale;
+    ///
+    /// assert_eq!(vec!["en-AU", "en", "default"], locale!(English, Australia).fallback_chain());
+    /// ```
+    pub fn fallback_chain(&self) -> Vec<String> {
+        let mut chain = Vec::with_capacity(3);
+        if self.region.is_some() {
+            chain.push(self.to_string());
+        }
+        chain.push(self.language.as_str().to_string());
+        chain.push(String::from(Self::DEFAULT_KEY));
+        chain
+    }
 }
 
 declare_api_enum! {
@@ -206,7 +1286,8 @@ declare_api_enum! {
         Hindi => "hi",
         Spanish => "es",
         French => "fr",
-        Portuguese => "pt"
+        Portuguese => "pt",
+        Arabic => "ar"
     }
 }
 
@@ -223,6 +1304,7 @@ declare_api_enum! {
         Japan => "JP",
         Mexico => "MX",
         Spain => "ES",
+        SaudiArabia => "SA",
         USA => "US"
     }
 }
@@ -291,11 +1373,66 @@ impl<'de> Deserialize<'de> for Locale {
     }
 }
 
-impl RequestEnvelope {
+impl<C> RequestEnvelope<C> {
+    /// Creates an `INFO` level span describing this request, with `request_id`, `request_type`, `intent`
+    /// and `locale` fields, so that every event a skill logs while handling the request carries them:
+    /// ```
+    /// # use alexa_sdk::RequestEnvelope;
+    /// fn handle(req: &RequestEnvelope) {
+    ///     let _span = req.span().entered();
+    ///     tracing::info!("handling request");
+    /// }
+    /// ```
+    #[cfg(feature = "tracing")]
+    pub fn span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "alexa_request",
+            request_id = %self.request.request_id,
+            request_type = %self.request.request_type.as_str(),
+            intent = self.intent_name(),
+            locale = self.locale().map(tracing::field::display),
+        )
+    }
+
+    /// retrieves the locale of the request. Some out-of-session requests, such as skill events, have no locale.
+    pub fn locale(&self) -> Option<&Locale> {
+        self.request.locale.as_ref()
+    }
+
     pub fn intent_type(&self) -> Option<&IntentType> {
         self.request.intent.as_ref().map(|i| &i.name)
     }
 
+    /// retrieves the name of the intent as it appears in the request, e.g. `"AMAZON.HelpIntent"` or `"MyIntent"`
+    pub fn intent_name(&self) -> Option<&str> {
+        self.intent_type().map(IntentType::as_str)
+    }
+
+    /// returns true if the request carries the intent with the given name
+    pub fn is_intent(&self, name: &str) -> bool {
+        self.intent_name() == Some(name)
+    }
+
+    /// returns a view of the intent and slots of a `CanFulfillIntentRequest`, for estimating whether
+    /// the skill can fulfill the request. `None` for any other request type.
+    pub fn can_fulfill_query(&self) -> Option<CanFulfillQuery<'_>> {
+        match self.request.request_type {
+            RequestType::CanFulfillIntentRequest => Some(CanFulfillQuery {
+                intent: self.request.intent.as_ref()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// returns a view of the arguments and source of an `Alexa.Presentation.APL.UserEvent`.
+    /// `None` for any other request type.
+    pub fn user_event(&self) -> Option<UserEvent<'_>> {
+        match self.request.request_type {
+            RequestType::AplUserEvent => Some(UserEvent { request: &self.request }),
+            _ => None,
+        }
+    }
+
     /// retrieves the string value of named slot from the request, if it exists
     pub fn slot_value(&self, slot: &str) -> Option<&String> {
         self.request
@@ -304,12 +1441,152 @@ impl RequestEnvelope {
             .value.as_ref()
     }
 
-    /// retrieves the attribute value with the given key, if it exists
+    /// retrieves the string value of named slot from the request, or the reason it has none:
+    /// ```
+    /// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+    /// #     "version": "1.0",
+    /// #     "context": { "System": {} },
+    /// #     "request": { "type": "IntentRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z",
+    /// #         "intent": { "name": "OrderPizza", "slots": { "size": { "name": "size" } } } }
+    /// # })).unwrap();
+    /// use alexa_sdk::request::SlotError;
+    ///
+    /// assert_eq!(req.try_slot_value("size"), Err(SlotError::EmptySlot(String::from("size"))));
+    /// assert_eq!(req.try_slot_value("topping"), Err(SlotError::NoSlot(String::from("topping"))));
+    /// ```
+    pub fn try_slot_value(&self, slot: &str) -> Result<&String, SlotError> {
+        self.request
+            .intent.as_ref().ok_or(SlotError::NoIntent)?
+            .get_slot(slot).ok_or_else(|| SlotError::NoSlot(String::from(slot)))?
+            .value.as_ref().ok_or_else(|| SlotError::EmptySlot(String::from(slot)))
+    }
+
+    /// the slots of the request intent as a [`SlotTree`], see [`Intent::slot_tree`].
+    /// Empty for requests without an intent.
+    pub fn slot_tree(&self) -> SlotTree<'_> {
+        self.request.intent.as_ref().map(Intent::slot_tree).unwrap_or_default()
+    }
+
+    /// retrieves the attribute value with the given key, if it exists.
+    /// Always `None` for requests without a session.
     pub fn attribute_value(&self, key: &str) -> Option<&String> {
         self.session.as_ref()?.attributes.as_ref()?.get(key)
     }
 
-    /// returns whether or not this is a new request
+    /// the `dialogState` of the request, for an `IntentRequest` in a skill with a dialog model
+    pub fn dialog_state(&self) -> Option<DialogState> {
+        self.request.dialog_state.as_deref().map(DialogState::from)
+    }
+
+    /// returns true if the request is the first turn of a multi-turn dialog, with `dialogState` `STARTED`
+    pub fn is_dialog_started(&self) -> bool {
+        self.dialog_state() == Some(DialogState::Started)
+    }
+
+    /// returns true if Alexa is collecting or confirming slots, with `dialogState` `IN_PROGRESS`
+    pub fn is_dialog_in_progress(&self) -> bool {
+        self.dialog_state() == Some(DialogState::InProgress)
+    }
+
+    /// returns true if every required slot is filled and confirmed, with `dialogState` `COMPLETED`, so the
+    /// skill can fulfill the intent. Check [`RequestEnvelope::is_intent_denied`] before fulfilling it.
+    pub fn is_dialog_complete(&self) -> bool {
+        self.dialog_state() == Some(DialogState::Completed)
+    }
+
+    /// returns true if the request intent was confirmed by the user, see [`Intent::is_confirmed`]
+    pub fn is_intent_confirmed(&self) -> bool {
+        self.request.intent.as_ref().is_some_and(Intent::is_confirmed)
+    }
+
+    /// returns true if the request intent was denied by the user, see [`Intent::is_denied`]
+    pub fn is_intent_denied(&self) -> bool {
+        self.request.intent.as_ref().is_some_and(Intent::is_denied)
+    }
+
+    /// returns true if the user confirmed the value of the named slot
+    pub fn is_slot_confirmed(&self, slot: &str) -> bool {
+        self.request.intent.as_ref()
+            .and_then(|i| i.get_slot(slot))
+            .is_some_and(|s| s.confirmation() == Some(ConfirmationStatus::Confirmed))
+    }
+
+    /// retrieves the error which ended the session, for a `SessionEndedRequest` with reason `ERROR`
+    pub fn session_end_error(&self) -> Option<&SessionEndedError> {
+        match self.request.request_type {
+            RequestType::SessionEndedRequest => self.request.error.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// retrieves the errors reported by an `Alexa.Presentation.APL.RuntimeError` request, which skills should
+    /// log, since the user may be looking at a blank or broken screen
+    pub fn apl_runtime_errors(&self) -> Option<&[AplRuntimeError]> {
+        match self.request.request_type {
+            RequestType::AplRuntimeError => self.request.errors.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// decodes the body of a household list items event, or returns `None` for other requests. Fetch the
+    /// changed items with [`crate::services::ListsApi::item`], except for deleted items.
+    pub fn household_list_event(&self) -> Option<Result<HouseholdListEvent, serde_json::Error>> {
+        match self.request.request_type {
+            RequestType::HouseholdListItemsCreated
+            | RequestType::HouseholdListItemsUpdated
+            | RequestType::HouseholdListItemsDeleted => self.request.body.as_ref().map(HouseholdListEvent::deserialize),
+            _ => None,
+        }
+    }
+
+    /// decodes the body of a reminder lifecycle event, or returns `None` for other requests:
+    /// ```
+    /// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+    /// #     "version": "1.0",
+    /// #     "context": { "System": {} },
+    /// #     "request": { "type": "Reminders.ReminderStatusChanged", "requestId": "id",
+    /// #         "timestamp": "2018-12-08T05:37:32Z", "body": { "alertToken": "token", "status": "COMPLETED" } }
+    /// # })).unwrap();
+    /// use alexa_sdk::request::ReminderStatus;
+    ///
+    /// let event = req.reminder_event().unwrap().unwrap();
+    /// if event.status == Some(ReminderStatus::Completed) {
+    ///     // stop tracking the reminders
+    ///     let _: Vec<&str> = event.tokens().collect();
+    /// }
+    /// ```
+    pub fn reminder_event(&self) -> Option<Result<ReminderEvent, serde_json::Error>> {
+        match self.request.request_type {
+            RequestType::ReminderCreated
+            | RequestType::ReminderUpdated
+            | RequestType::ReminderDeleted
+            | RequestType::ReminderStarted
+            | RequestType::ReminderStatusChanged => self.request.body.as_ref().map(ReminderEvent::deserialize),
+            _ => None,
+        }
+    }
+
+    /// decodes the payload of a DataStore package event, or returns `None` for other requests. Answer an
+    /// `UpdateRequest` by putting the data of `to_version` with [`crate::services::DataStoreApi::commands`].
+    pub fn datastore_package_event(&self) -> Option<Result<DataStorePackageEvent, serde_json::Error>> {
+        match self.request.request_type {
+            RequestType::DataStoreUsagesInstalled
+            | RequestType::DataStoreUsagesRemoved
+            | RequestType::DataStoreUpdateRequest
+            | RequestType::DataStoreInstallationError => {
+                self.request.payload.as_ref().map(DataStorePackageEvent::deserialize)
+            }
+            _ => None,
+        }
+    }
+
+    /// returns whether or not this request carries a `session` object. Requests sent outside of a
+    /// skill session, such as `PlaybackController.*` and `System.ExceptionEncountered`, have no session.
+    pub fn has_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// returns whether or not this is a new request. Always `false` for requests without a session.
     pub fn is_new(&self) -> bool {
         match &self.session {
             Some(s) => s.new,
@@ -318,6 +1595,124 @@ impl RequestEnvelope {
     }
 }
 
+impl RequestEnvelope {
+    /// Parses a request body, keeping a copy of the original bytes for signature verification or debugging.
+    /// With the `tracing` feature, emits a `DEBUG` event for each request parsed and a `WARN` event for each
+    /// body which fails to parse.
+    pub fn from_slice(bytes: &[u8]) -> Result<RawRequestEnvelope, serde_json::Error> {
+        let parsed = serde_json::from_slice::<RequestEnvelope>(bytes);
+        #[cfg(feature = "tracing")]
+        match &parsed {
+            Ok(env) => tracing::debug!(
+                request_id = %env.request.request_id,
+                request_type = %env.request.request_type.as_str(),
+                bytes = bytes.len(),
+                "parsed Alexa request"
+            ),
+            Err(error) => tracing::warn!(%error, bytes = bytes.len(), "failed to parse Alexa request"),
+        }
+        Ok(RawRequestEnvelope {
+            envelope: parsed?,
+            raw: bytes.to_vec(),
+        })
+    }
+}
+
+impl<C: RequestContext> RequestEnvelope<C> {
+    /// Returns a copy of this request which is safe to log: access tokens, and the code and token of an
+    /// `Alexa.Authorization.Grant`, are replaced by [`REDACTED`], and
+    /// user, person and device ids are masked except for their `amzn1.ask.*.` prefix and last four characters,
+    /// which is enough to correlate log entries.
+    pub fn redacted(&self) -> Self
+    where
+        C: Clone,
+    {
+        let mut req = self.clone();
+        let system = req.context.system_mut();
+        redact(&mut system.api_access_token);
+        if let Some(user) = system.user.as_mut() {
+            user.redact();
+        }
+        if let Some(person) = system.person.as_mut() {
+            person.person_id = mask_id(&person.person_id);
+            redact(&mut person.access_token);
+        }
+        if let Some(device) = system.device.as_mut() {
+            device.device_id = mask_id(&device.device_id);
+        }
+        if let Some(session) = req.session.as_mut() {
+            session.user.redact();
+        }
+        if req.request.request_type == RequestType::AlexaAuthorizationGrant {
+            if let Some(body) = req.request.body.as_mut() {
+                for secret in ["/grant/code", "/grantee/token"] {
+                    if let Some(value) = body.pointer_mut(secret) {
+                        *value = serde_json::Value::from(REDACTED);
+                    }
+                }
+            }
+        }
+        req
+    }
+
+    /// the treatment the user receives in the skill experiment `id`, e.g. `C` for control or `T1`, or `None`
+    /// if the user isn't part of the experiment. See [`crate::ResponseEnvelope::trigger_experiment`].
+    pub fn experiment_treatment(&self, id: &str) -> Option<&str> {
+        self.context.experimentation()?
+            .active_experiments.iter()
+            .find(|e| e.id == id)
+            .map(|e| e.treatment_id.as_str())
+    }
+
+    /// decodes a context object which this crate doesn't model yet, such as a newly launched interface,
+    /// or returns `None` when the request context doesn't contain it:
+    /// ```
+    /// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+    /// #     "version": "1.0",
+    /// #     "context": { "System": {}, "Extensions": { "available": { "aplext:backstack:10": {} } } },
+    /// #     "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+    /// # })).unwrap();
+    /// use std::collections::HashMap;
+    /// use serde_json::Value;
+    ///
+    /// let extensions = req.context_extra::<HashMap<String, Value>>("Extensions").unwrap().unwrap();
+    /// assert!(extensions.contains_key("available"));
+    /// ```
+    pub fn context_extra<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<Result<T, serde_json::Error>> {
+        self.context.extra().get(key).map(|v| T::deserialize(v))
+    }
+
+    /// retrieves the user id, from `context.System.user` or else `session.user`
+    pub fn user_id(&self) -> Option<&String> {
+        self.context.system().user.as_ref()
+            .map(|u| &u.user_id)
+            .or_else(|| self.session.as_ref().map(|s| &s.user.user_id))
+    }
+
+    /// retrieves the person id of the recognized speaker, if any
+    pub fn person_id(&self) -> Option<&String> {
+        self.context.system().person.as_ref().map(|p| &p.person_id)
+    }
+
+    /// retrieves the id of the requesting device
+    pub fn device_id(&self) -> Option<&String> {
+        self.context.system().device.as_ref().map(|d| &d.device_id)
+    }
+
+    /// returns true if the requesting device declares support for the named interface
+    pub fn supports_interface(&self, interface: &str) -> bool {
+        self.context.system().device.as_ref().is_some_and(|d| d.supports(interface))
+    }
+
+    /// retrieves the account linking access token for the user, if the user has linked their account.
+    /// The token is read from `context.System.user`, falling back to `session.user`.
+    pub fn account_link_token(&self) -> Option<&String> {
+        self.context.system().user.as_ref()
+            .and_then(|u| u.access_token.as_ref())
+            .or_else(|| self.session.as_ref()?.user.access_token.as_ref())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -325,47 +1720,174 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_version() {
+    fn test_version() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert_eq!(req.version, "1.0");
+    }
+
+    #[test]
+    fn test_locale() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert_eq!(req.locale(), Some(&locale!(English, USA)));
+        assert_eq!(req.locale().unwrap().to_string(), "en-US");
+        assert_eq!(req.locale().unwrap().parts(), (&Language::English, Some(&Region::USA)));
+        assert_eq!(Locale::from("ar-SA"), locale!(Arabic, SaudiArabia));
+        assert_eq!(locale!(Arabic, SaudiArabia).to_string(), "ar-SA");
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        assert_eq!(vec!["en-US", "en", "default"], locale!(English, USA).fallback_chain());
+        assert_eq!(vec!["fr", "default"], Locale::from("fr").fallback_chain());
+        assert_eq!(vec!["nl-NL", "nl", "default"], Locale::from("nl-NL").fallback_chain());
+        assert_eq!(Locale::from("nl-NL").region, Some(Region::Other(String::from("NL"))));
+    }
+
+    #[test]
+    fn test_missing_locale() {
+        let mut value = default_req();
+        value["request"].as_object_mut().unwrap().remove("locale");
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert_eq!(req.locale(), None);
+    }
+
+    #[test]
+    fn test_is_english() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert!(req.locale().unwrap().is_english());
+    }
+
+    #[test]
+    fn test_is_spanish() {
+        let req: RequestEnvelope = serde_json::from_value(default_spanish_req()).unwrap();
+        assert!(req.locale().unwrap().is_spanish());
+    }
+
+    #[test]
+    fn test_repeat_leaves_skill_attributes_alone() {
+        use crate::response::{ResponseEnvelope, Speech, LAST_SPEECH_ATTRIBUTE};
+
+        let mut value = default_french_req();
+        let req: RequestEnvelope = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(ResponseEnvelope::repeat_from(&req), None);
+
+        value["session"]["attributes"][LAST_SPEECH_ATTRIBUTE] = json!("Jupiter has the shortest day");
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        let repeated = ResponseEnvelope::repeat_from(&req).unwrap();
+        assert_eq!(repeated.response.output_speech, Some(Speech::plain("Jupiter has the shortest day")));
+        assert_eq!(req.attribute_value("lastSpeech").unwrap(), "Jupiter has the shortest day of all the planets");
+    }
+
+    #[test]
+    fn test_is_french() {
+        let req: RequestEnvelope = serde_json::from_value(default_french_req()).unwrap();
+        assert!(req.locale().unwrap().is_french());
+    }
+
+    #[test]
+    fn test_intent() {
         let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
-        assert_eq!(req.version, "1.0");
+        assert_eq!(req.intent_type(), Some(&IntentType::Other(String::from("hello"))));
+        assert!(!req.intent_type().unwrap().is_builtin());
     }
 
     #[test]
-    fn test_locale() {
-        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
-        assert_eq!(req.request.locale, locale!(English, USA));
-        assert_eq!(req.request.locale.to_string(), "en-US");
-        assert_eq!(req.request.locale.parts(), (&Language::English, Some(&Region::USA)));
+    fn test_multi_value_slot() {
+        let resolutions = |id: &str, name: &str| json!({
+            "resolutionsPerAuthority": [{
+                "authority": "amzn1.er-authority.echo-sdk.SKILL.Grocery",
+                "status": { "code": "ER_SUCCESS_MATCH" },
+                "values": [{ "value": { "name": name, "id": id } }]
+            }]
+        });
+        let slot: Slot = serde_json::from_value(json!({
+            "name": "items",
+            "confirmationStatus": "NONE",
+            "slotValue": {
+                "type": "List",
+                "values": [
+                    { "type": "Simple", "value": "apples", "resolutions": resolutions("APPLE", "apple") },
+                    { "type": "Simple", "value": "oat milk", "resolutions": resolutions("OAT_MILK", "oat milk") },
+                    { "type": "Simple", "value": "zucchini" }
+                ]
+            }
+        })).unwrap();
+        let values = slot.resolved_values();
+        assert_eq!(values.iter().map(|v| v.spoken).collect::<Vec<_>>(), vec!["apples", "oat milk", "zucchini"]);
+        assert_eq!(values.iter().map(|v| v.id()).collect::<Vec<_>>(), vec![Some("APPLE"), Some("OAT_MILK"), None]);
+        assert_eq!(values[0].entity.unwrap().name, "apple");
+
+        let req: RequestEnvelope = serde_json::from_value(req_with_slots()).unwrap();
+        let slot = req.request.intent.as_ref().unwrap().get_slot("name").unwrap();
+        assert_eq!(slot.resolved_values().iter().map(|v| v.spoken).collect::<Vec<_>>(), vec!["bob"]);
     }
 
     #[test]
-    fn test_is_english() {
-        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
-        assert!(req.request.locale.is_english());
+    fn test_resolution_without_id() {
+        let resolution: Resolution = serde_json::from_value(json!({
+            "resolutionsPerAuthority": [{
+                "authority": "amzn1.er-authority.echo-sdk.SKILL.Colors",
+                "status": { "code": "ER_SUCCESS_MATCH" },
+                "values": [{ "value": { "name": "crimson" } }]
+            }]
+        })).unwrap();
+        assert!(resolution.resolutions_per_authority[0].is_match());
+        let value = resolution.first_match().unwrap();
+        assert_eq!(value.id, None);
+        assert_eq!(value.key(), "crimson");
+
+        let value: Value = serde_json::from_value(json!({ "name": "red", "id": "RED" })).unwrap();
+        assert_eq!(value.key(), "RED");
     }
 
     #[test]
-    fn test_is_spanish() {
-        let req: RequestEnvelope = serde_json::from_value(default_spanish_req()).unwrap();
-        assert!(req.request.locale.is_spanish());
+    fn test_resolution_status() {
+        let authority: ResolutionsPerAuthority = serde_json::from_value(json!({
+            "authority": "amzn1.er-authority.echo-sdk.SKILL.Colors",
+            "status": { "code": "ER_SUCCESS_NO_MATCH" },
+            "values": []
+        })).unwrap();
+        assert_eq!(authority.status.code, StatusCode::SuccessNoMatch);
+        assert!(!authority.is_match());
+        assert_eq!(StatusCode::from("ER_ERROR_TIMEOUT"), StatusCode::ErrorTimeout);
     }
 
     #[test]
-    fn test_is_french() {
-        let req: RequestEnvelope = serde_json::from_value(default_french_req()).unwrap();
-        assert!(req.request.locale.is_french());
+    fn test_builtin_intents() {
+        assert_eq!(IntentType::from("AMAZON.ScrollDownIntent"), IntentType::ScrollDown);
+        assert_eq!(IntentType::NavigateSettings.as_str(), "AMAZON.NavigateSettingsIntent");
+        assert!(IntentType::PageUp.is_builtin());
+        assert!(IntentType::from("AMAZON.SearchAction<object@WeatherForecast>").is_builtin());
+        assert!(!IntentType::from("OrderPizza").is_builtin());
     }
 
     #[test]
-    fn test_intent() {
+    fn test_intent_name() {
         let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
-        assert_eq!(req.intent_type(), Some(&IntentType::Other(String::from("hello"))));
+        assert_eq!(req.intent_name(), Some("hello"));
+        assert!(req.is_intent("hello"));
+        assert!(!req.is_intent("AMAZON.HelpIntent"));
+
+        let mut value = default_req();
+        value["request"]["intent"]["name"] = json!("AMAZON.HelpIntent");
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert_eq!(req.intent_name(), Some("AMAZON.HelpIntent"));
+        assert!(req.is_intent("AMAZON.HelpIntent"));
+
+        let req: RequestEnvelope = serde_json::from_value(exception_encountered_req()).unwrap();
+        assert_eq!(req.intent_name(), None);
+        assert!(!req.is_intent("hello"));
     }
 
     #[test]
     fn test_slot() {
         let req: RequestEnvelope = serde_json::from_value(req_with_slots()).unwrap();
         assert_eq!(req.slot_value("name"), Some(&String::from("bob")));
+        assert_eq!(req.try_slot_value("name"), Ok(&String::from("bob")));
+        assert_eq!(req.try_slot_value("age"), Err(SlotError::NoSlot(String::from("age"))));
+
+        let req: RequestEnvelope = serde_json::from_value(exception_encountered_req()).unwrap();
+        assert_eq!(req.try_slot_value("name"), Err(SlotError::NoIntent));
     }
 
     #[test]
@@ -395,6 +1917,654 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_has_session() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert!(req.has_session());
+    }
+
+    #[test]
+    fn test_playback_controller_requests() {
+        let cases = [
+            ("PlaybackController.NextCommandIssued", RequestType::PlaybackControllerNextCommandIssued),
+            ("PlaybackController.PauseCommandIssued", RequestType::PlaybackControllerPauseCommandIssued),
+            ("PlaybackController.PlayCommandIssued", RequestType::PlaybackControllerPlayCommandIssued),
+            ("PlaybackController.PreviousCommandIssued", RequestType::PlaybackControllerPreviousCommandIssued),
+        ];
+        for (name, request_type) in cases {
+            let req: RequestEnvelope = serde_json::from_value(playback_controller_req(name)).unwrap();
+            assert_eq!(req.request.request_type, request_type);
+            assert_sessionless(&req);
+            assert_eq!(req.context.audio_player.unwrap().token, Some(String::from("track-1")));
+        }
+    }
+
+    #[test]
+    fn test_exception_encountered_request() {
+        let req: RequestEnvelope = serde_json::from_value(exception_encountered_req()).unwrap();
+        assert_eq!(req.request.request_type, RequestType::SystemExceptionEncountered);
+        assert_eq!(req.request.error.as_ref().unwrap().error_type, SessionEndedErrorType::InvalidResponse);
+        assert!(req.session_end_error().is_none());
+        assert_sessionless(&req);
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let body = serde_json::to_vec_pretty(&default_req()).unwrap();
+        let req = RequestEnvelope::from_slice(&body).unwrap();
+        assert_eq!(req.raw(), &body[..]);
+        assert_eq!(req.locale().unwrap().to_string(), "en-US");
+        let (raw, envelope) = req.into_parts();
+        assert_eq!(raw, body);
+        assert!(envelope.is_new());
+
+        assert!(RequestEnvelope::from_slice(b"{}").is_err());
+    }
+
+    #[test]
+    fn test_redacted() {
+        let mut value = default_req();
+        value["context"]["System"]["user"]["accessToken"] = json!("linked-token");
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        let redacted = req.redacted();
+
+        assert_eq!(redacted.context.system.api_access_token.as_ref().unwrap(), REDACTED);
+        let user = redacted.context.system.user.as_ref().unwrap();
+        assert_eq!(user.access_token.as_ref().unwrap(), REDACTED);
+        assert_eq!(user.user_id, "amzn1.ask.account.*****erid");
+        assert!(redacted.context.system.device.as_ref().unwrap().device_id.starts_with("amzn1.ask.device.*"));
+        assert_eq!(redacted.session.as_ref().unwrap().user.user_id, user.user_id);
+        assert_eq!(req.context.system.user.as_ref().unwrap().access_token.as_ref().unwrap(), "linked-token");
+
+        assert_eq!(mask_id("abcdefgh"), "****efgh");
+        assert_eq!(mask_id("a.b"), "a.b");
+    }
+
+    #[test]
+    fn test_redacted_grant() {
+        let mut value = default_req();
+        value["request"] = json!({
+            "type": "Alexa.Authorization.Grant",
+            "requestId": "amzn1.echo-api.request.id",
+            "timestamp": "2018-12-08T05:37:32Z",
+            "body": {
+                "grant": { "type": "OAuth2.AuthorizationCode", "code": "ANUbUKCJqlBOpMhwYWxU" },
+                "grantee": { "type": "BearerToken", "token": "access-token-from-skill" }
+            }
+        });
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        let logged = serde_json::to_string(&req.redacted()).unwrap();
+        assert!(!logged.contains("ANUbUKCJqlBOpMhwYWxU"));
+        assert!(!logged.contains("access-token-from-skill"));
+        assert_eq!(req.redacted().request.body.unwrap()["grant"]["code"], REDACTED);
+    }
+
+    #[test]
+    fn test_apl_runtime_errors() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert!(req.apl_runtime_errors().is_none());
+
+        let mut value = default_req();
+        value["request"] = json!({
+            "type": "Alexa.Presentation.APL.RuntimeError",
+            "requestId": "amzn1.echo-api.request.REQUEST",
+            "timestamp": "2025-03-17T23:27:29Z",
+            "locale": "en-US",
+            "token": "main",
+            "errors": [
+                {
+                    "type": "LIST_ERROR",
+                    "reason": "INCONSISTENT_RANGE",
+                    "listId": "my-list",
+                    "message": "Inconsistent range"
+                },
+                { "type": "SOME_NEW_ERROR" }
+            ]
+        });
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert_eq!(req.request.request_type, RequestType::AplRuntimeError);
+        assert_eq!(req.request.token.as_ref().unwrap(), "main");
+        let errors = req.apl_runtime_errors().unwrap();
+        assert_eq!(errors[0].error_type, AplRuntimeErrorType::ListError);
+        assert_eq!(errors[0].list_id.as_ref().unwrap(), "my-list");
+        assert_eq!(errors[1].error_type, AplRuntimeErrorType::Other(String::from("SOME_NEW_ERROR")));
+    }
+
+    #[test]
+    fn test_account_link_token() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert_eq!(req.account_link_token(), None);
+
+        let mut value = default_req();
+        value["context"]["System"]["user"]["accessToken"] = json!("linked-token");
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert_eq!(req.account_link_token(), Some(&String::from("linked-token")));
+
+        let mut value = playback_controller_req("PlaybackController.PlayCommandIssued");
+        value["context"]["System"]["user"]["accessToken"] = json!("linked-token");
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert_eq!(req.account_link_token(), Some(&String::from("linked-token")));
+    }
+
+    #[test]
+    fn test_session_end_error() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert!(req.session_end_error().is_none());
+
+        let mut value = default_req();
+        value["request"] = json!({
+            "type": "SessionEndedRequest",
+            "requestId": "amzn1.echo-api.request.REQUEST",
+            "timestamp": "2025-03-17T23:27:29Z",
+            "locale": "en-US",
+            "reason": "ERROR",
+            "error": {
+                "type": "INVALID_RESPONSE",
+                "message": "The response is invalid."
+            }
+        });
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert_eq!(req.request.reason, Some(SessionEndedReason::Error));
+        let error = req.session_end_error().unwrap();
+        assert_eq!(error.error_type, SessionEndedErrorType::InvalidResponse);
+        assert_eq!(error.message, Some(String::from("The response is invalid.")));
+    }
+
+    #[test]
+    fn test_supports_interface() {
+        let req: RequestEnvelope = serde_json::from_value(with_playback_intent()).unwrap();
+        assert!(req.supports_interface("AudioPlayer"));
+        assert!(!req.supports_interface("Display"));
+
+        let req: RequestEnvelope = serde_json::from_value(req_with_slots()).unwrap();
+        assert!(req.supports_interface("Display"));
+        assert!(!req.supports_interface("AudioPlayer"));
+    }
+
+    #[test]
+    fn test_intent_builder() {
+        let intent = Intent::builder(IntentType::Other(String::from("OrderPizza")))
+            .slot("size", "large")
+            .slot("crust", "thin")
+            .confirm_slot("crust")
+            .build();
+        assert_eq!(
+            json!({
+                "name": "OrderPizza",
+                "confirmationStatus": "NONE",
+                "slots": {
+                    "size": {
+                        "name": "size",
+                        "value": "large",
+                        "confirmationStatus": "NONE"
+                    },
+                    "crust": {
+                        "name": "crust",
+                        "value": "thin",
+                        "confirmationStatus": "CONFIRMED"
+                    }
+                }
+            }),
+            serde_json::to_value(intent).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        let offsets: Vec<Offset> = serde_json::from_value(json!([-1, 0, 1500])).unwrap();
+        assert_eq!(offsets, vec![Offset::ZERO, Offset::ZERO, Offset::from(Duration::from_millis(1500))]);
+        assert_eq!(offsets[2].as_duration(), Duration::from_millis(1500));
+        assert_eq!(json!([0, 0, 1500]), serde_json::to_value(offsets).unwrap());
+
+        let req: RequestEnvelope = serde_json::from_value(playback_controller_req("PlaybackController.PlayCommandIssued")).unwrap();
+        assert_eq!(req.context.audio_player.unwrap().offset_in_milliseconds, Some(Offset::from_millis(1000)));
+    }
+
+    #[test]
+    fn test_user_event() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert!(req.user_event().is_none());
+
+        let mut value = default_req();
+        value["request"] = json!({
+            "type": "Alexa.Presentation.APL.UserEvent",
+            "requestId": "amzn1.echo-api.request.REQUEST",
+            "timestamp": "2025-03-17T23:27:29Z",
+            "locale": "en-US",
+            "token": "main",
+            "arguments": ["select", 3, { "id": "B00X" }],
+            "source": {
+                "type": "TouchWrapper",
+                "handler": "Press",
+                "id": "item-3",
+                "value": false
+            },
+            "components": { "search": "pizza" }
+        });
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        let event = req.user_event().unwrap();
+        assert_eq!(event.token(), Some("main"));
+        assert_eq!(event.arguments().len(), 3);
+        assert_eq!(event.argument::<String>(0).unwrap().unwrap(), "select");
+        assert_eq!(event.argument::<usize>(1).unwrap().unwrap(), 3);
+        assert!(event.argument::<usize>(0).unwrap().is_err());
+        assert!(event.argument::<usize>(3).is_none());
+        assert_eq!(event.argument::<HashMap<String, String>>(2).unwrap().unwrap()["id"], "B00X");
+        assert_eq!(event.source().unwrap().handler, "Press");
+        assert_eq!(event.component("search"), Some(&json!("pizza")));
+    }
+
+    #[test]
+    fn test_can_fulfill_query() {
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert!(req.can_fulfill_query().is_none());
+
+        let req: RequestEnvelope = serde_json::from_value(can_fulfill_req()).unwrap();
+        assert!(!req.has_session());
+        let query = req.can_fulfill_query().unwrap();
+        assert_eq!(query.intent_name(), "FindRecipe");
+        assert_eq!(query.slot_value("dish"), Some(&String::from("lasagna")));
+        assert_eq!(query.slot_value("cuisine"), None);
+        let mut names: Vec<&str> = query.slots().map(|s| s.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["cuisine", "dish"]);
+    }
+
+    fn can_fulfill_req() -> serde_json::Value {
+        json!({
+            "version": "1.0",
+            "context": {
+                "System": {
+                    "application": {
+                        "applicationId": "amzn1.ask.skill.myappid"
+                    },
+                    "user": {
+                        "userId": "amzn1.ask.account.theuserid"
+                    },
+                    "apiEndpoint": "https://api.amazonalexa.com"
+                }
+            },
+            "request": {
+                "type": "CanFulfillIntentRequest",
+                "requestId": "amzn1.echo-api.request.REQUEST",
+                "timestamp": "2025-03-17T23:27:29Z",
+                "locale": "en-US",
+                "intent": {
+                    "name": "FindRecipe",
+                    "slots": {
+                        "dish": {
+                            "name": "dish",
+                            "value": "lasagna"
+                        },
+                        "cuisine": {
+                            "name": "cuisine"
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_playback_state() {
+        let req: RequestEnvelope = serde_json::from_value(with_playback_intent()).unwrap();
+        let player = req.context.audio_player.unwrap();
+        assert_eq!(player.player_activity, Some(PlayerActivity::Idle));
+        assert_eq!(player.state(), PlaybackState::Idle);
+        assert_eq!(player.state().position(), None);
+
+        let player: AudioPlayer = serde_json::from_value(json!({
+            "token": "track-1",
+            "offsetInMilliseconds": 5000,
+            "playerActivity": "PAUSED"
+        })).unwrap();
+        let position = StreamPosition { token: "track-1", offset: Offset::from_millis(5000) };
+        assert_eq!(player.state(), PlaybackState::Paused(position));
+        assert_eq!(player.state().position(), Some(position));
+
+        let player: AudioPlayer = serde_json::from_value(json!({ "playerActivity": "PLAYING" })).unwrap();
+        assert_eq!(player.state(), PlaybackState::Unknown);
+    }
+
+    #[test]
+    fn test_viewport_profile() {
+        let req: RequestEnvelope = serde_json::from_value(with_playback_intent()).unwrap();
+        let viewport = req.context.viewport.unwrap();
+        assert_eq!(viewport.mode, Some(ViewportMode::Hub));
+        assert_eq!(viewport.dp_size(), Some((1280, 800)));
+        assert_eq!(viewport.profile(), Some(ViewportProfile::HubLandscapeLarge));
+        assert_eq!(viewport.touch.as_ref().unwrap(), &vec![ViewportTouch::Single]);
+        assert!(viewport.supports_touch());
+        assert!(viewport.supports_direction_keys());
+
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        let viewport = req.context.viewport.unwrap();
+        assert_eq!(viewport.mode, None);
+        assert_eq!(viewport.profile(), None);
+
+        let viewport = |width: u32, height: u32, dpi: u32| serde_json::from_value::<Viewport>(json!({
+            "mode": "HUB",
+            "shape": "RECTANGLE",
+            "pixelWidth": width,
+            "pixelHeight": height,
+            "dpi": dpi
+        })).unwrap();
+        let profile = |width: u32, height: u32, dpi: u32| viewport(width, height, dpi).profile();
+        assert_eq!(profile(960, 480, 160), Some(ViewportProfile::HubLandscapeSmall));
+        assert_eq!(profile(1280, 800, 213), Some(ViewportProfile::HubLandscapeMedium));
+        assert_eq!(profile(1920, 1080, 160), Some(ViewportProfile::HubLandscapeXLarge));
+        assert_eq!(profile(1080, 1920, 160), Some(ViewportProfile::HubPortraitMedium));
+        assert_eq!(viewport(u32::MAX, 1080, 320).dp_size(), Some((u32::MAX / 2, 540)));
+        assert_eq!(viewport(u32::MAX, 1080, 80).dp_size(), None);
+    }
+
+    #[test]
+    fn test_slot_defaults() {
+        let defaults = SlotDefaults::new()
+            .slot("hello", "name", "world")
+            .slot("hello", "greeting", "hi")
+            .slot("goodbye", "name", "moon");
+
+        let mut req: RequestEnvelope = serde_json::from_value(req_with_slots()).unwrap();
+        defaults.apply(&mut req);
+        assert_eq!(req.slot_value("name").unwrap(), "bob");
+        assert_eq!(req.slot_value("greeting").unwrap(), "hi");
+
+        let mut req: RequestEnvelope = serde_json::from_value(req_with_slots()).unwrap();
+        req.request.intent = Some(Intent::builder("other").build());
+        defaults.apply(&mut req);
+        assert!(req.slot_value("name").is_none());
+    }
+
+    #[test]
+    fn test_slot_tree() {
+        let mut req: RequestEnvelope = serde_json::from_value(req_with_slots()).unwrap();
+        assert_eq!(req.slot_tree()["name"].value().unwrap(), "bob");
+        assert!(req.slot_tree()["missing"]["deeper"].is_empty());
+
+        req.request.intent = Some(Intent::builder("AMAZON.PlaybackAction<object@MusicCreativeWork>")
+            .slot("object.byArtist.name", "the beatles")
+            .slot("object.name", "help")
+            .slot("object.type", "song")
+            .build());
+        let tree = req.slot_tree();
+        assert_eq!(tree["object"]["byArtist"]["name"].value().unwrap(), "the beatles");
+        assert_eq!(tree["object"]["name"].value().unwrap(), "help");
+        assert_eq!(tree["object"].children.len(), 3);
+        assert!(tree["object"].slot.is_none());
+        assert!(tree.get("subject").is_none());
+
+        req.request.intent = None;
+        assert!(req.slot_tree().is_empty());
+    }
+
+    #[test]
+    fn test_apl_visual_context() {
+        let mut value = default_req();
+        value["context"]["Alexa.Presentation.APL"] = json!({
+            "token": "menu",
+            "version": "AplRuntime-1.9",
+            "componentsVisibleOnScreen": [{
+                "uid": ":1000",
+                "position": "1280x800+0+0:0",
+                "type": "mixed",
+                "tags": { "viewport": {} },
+                "children": [{
+                    "uid": ":1002",
+                    "id": "items",
+                    "type": "mixed",
+                    "position": "1280x600+0+100:0",
+                    "tags": { "list": { "itemCount": 10, "lowestIndexSeen": 0, "highestIndexSeen": 3 }, "scrollable": { "direction": "vertical", "allowForward": true, "allowBackwards": false } },
+                    "entities": []
+                }]
+            }]
+        });
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        let apl = req.context.apl.as_ref().unwrap();
+        assert_eq!(apl.token.as_deref(), Some("menu"));
+        let list = apl.component("items").unwrap();
+        assert_eq!(list.uid, ":1002");
+        assert_eq!(list.tags.as_ref().unwrap()["list"]["itemCount"], 10);
+        assert!(apl.component("missing").is_none());
+        assert!(!req.context.extra.contains_key("Alexa.Presentation.APL"));
+    }
+
+    #[test]
+    fn test_context_extra() {
+        let req: RequestEnvelope = serde_json::from_value(with_playback_intent()).unwrap();
+        let viewports = req.context_extra::<Vec<serde_json::Value>>("Viewports").unwrap().unwrap();
+        assert_eq!(viewports[0]["id"], "medHub");
+        assert!(req.context_extra::<String>("Viewports").unwrap().is_err());
+        assert!(req.context_extra::<serde_json::Value>("Missing").is_none());
+        assert!(!req.context.extra.contains_key("System"));
+
+        let round_trip = serde_json::to_value(&req).unwrap();
+        assert_eq!(round_trip["context"]["Viewports"][0]["id"], "medHub");
+    }
+
+    #[test]
+    fn test_experiment_treatment() {
+        let mut value = default_req();
+        value["context"]["Experimentation"] = json!({
+            "activeExperiments": [{ "id": "exp-1", "treatmentId": "T1" }]
+        });
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert_eq!(req.experiment_treatment("exp-1"), Some("T1"));
+        assert_eq!(req.experiment_treatment("exp-2"), None);
+
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert_eq!(req.experiment_treatment("exp-1"), None);
+    }
+
+    #[test]
+    fn test_household_list_event() {
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "context": { "System": { "apiEndpoint": "https://api.amazonalexa.com", "apiAccessToken": "TOKEN" } },
+            "request": {
+                "type": "AlexaHouseholdListEvent.ItemsCreated",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "eventCreationTime": "2018-12-08T05:37:31Z",
+                "eventPublishingTime": "2018-12-08T05:37:32Z",
+                "body": {
+                    "listId": "YW16bjEuYWNjb3VudC5TSE9QUElOR19JVEVN",
+                    "listItemIds": ["item-1", "item-2"]
+                }
+            }
+        })).unwrap();
+        assert_eq!(req.request.request_type, RequestType::HouseholdListItemsCreated);
+        let event = req.household_list_event().unwrap().unwrap();
+        assert_eq!(event.list_id, "YW16bjEuYWNjb3VudC5TSE9QUElOR19JVEVN");
+        assert_eq!(event.list_item_ids, vec!["item-1", "item-2"]);
+
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert!(req.household_list_event().is_none());
+    }
+
+    #[test]
+    fn test_dialog_predicates() {
+        let mut value = default_req();
+        value["request"]["dialogState"] = json!("COMPLETED");
+        value["request"]["intent"] = json!({
+            "name": "OrderPizza",
+            "confirmationStatus": "DENIED",
+            "slots": { "size": { "name": "size", "value": "large", "confirmationStatus": "CONFIRMED" } }
+        });
+        let req: RequestEnvelope = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(req.dialog_state(), Some(DialogState::Completed));
+        assert!(req.is_dialog_complete());
+        assert!(!req.is_dialog_started() && !req.is_dialog_in_progress());
+        assert!(req.is_intent_denied() && !req.is_intent_confirmed());
+        assert!(req.is_slot_confirmed("size"));
+        assert!(!req.is_slot_confirmed("topping"));
+        let intent = req.request.intent.as_ref().unwrap();
+        assert_eq!(intent.confirmation(), Some(ConfirmationStatus::Denied));
+        assert_eq!(intent.slots.as_ref().unwrap()["size"].confirmation(), Some(ConfirmationStatus::Confirmed));
+
+        value["request"]["dialogState"] = json!("IN_PROGRESS");
+        value["request"]["intent"]["confirmationStatus"] = json!("CONFIRMED");
+        let req: RequestEnvelope = serde_json::from_value(value).unwrap();
+        assert!(req.is_dialog_in_progress());
+        assert!(req.is_intent_confirmed());
+
+        let req: RequestEnvelope = serde_json::from_value(default_req()).unwrap();
+        assert_eq!(req.dialog_state(), None);
+        assert!(!req.is_dialog_complete() && !req.is_intent_confirmed() && !req.is_intent_denied());
+    }
+
+    #[test]
+    fn test_reminder_event() {
+        let event = |request_type: &str, body: serde_json::Value| envelope(json!({}), json!({ "type": request_type, "body": body }));
+        let req = event("Reminders.ReminderDeleted", json!({ "alertTokens": ["a", "b"] }));
+        assert_eq!(req.request.request_type, RequestType::ReminderDeleted);
+        assert_eq!(req.reminder_event().unwrap().unwrap().tokens().collect::<Vec<_>>(), vec!["a", "b"]);
+
+        let req = event("Reminders.ReminderStatusChanged", json!({ "alertToken": "a", "status": "COMPLETED" }));
+        let changed = req.reminder_event().unwrap().unwrap();
+        assert_eq!(changed.status, Some(ReminderStatus::Completed));
+        assert_eq!(changed.tokens().collect::<Vec<_>>(), vec!["a"]);
+
+        let req = event("AlexaHouseholdListEvent.ItemsCreated", json!({ "listId": "list" }));
+        assert!(req.reminder_event().is_none());
+    }
+
+    #[test]
+    fn test_datastore_package_event() {
+        let event = |request_type: &str, payload: serde_json::Value| envelope(json!({}), json!({ "type": request_type, "payload": payload }));
+        let req = event("Alexa.DataStore.PackageManager.UpdateRequest", json!({
+            "packageId": "scores",
+            "fromVersion": "1.0",
+            "toVersion": "1.1"
+        }));
+        assert_eq!(req.request.request_type, RequestType::DataStoreUpdateRequest);
+        let update = req.datastore_package_event().unwrap().unwrap();
+        assert_eq!(update.package_id, "scores");
+        assert_eq!((update.from_version.as_deref(), update.to_version.as_deref()), (Some("1.0"), Some("1.1")));
+
+        let req = event("Alexa.DataStore.PackageManager.UsagesInstalled", json!({
+            "packageId": "scores",
+            "packageVersion": "1.0",
+            "usages": [{ "instances": [{ "instanceId": "widget-1", "location": "FAVORITE" }] }]
+        }));
+        assert_eq!(req.request.request_type, RequestType::DataStoreUsagesInstalled);
+        let installed = req.datastore_package_event().unwrap().unwrap();
+        assert_eq!(installed.usages[0].instances[0].instance_id, "widget-1");
+
+        let req = event("Alexa.DataStore.PackageManager.InstallationError", json!({
+            "packageId": "scores",
+            "version": "1.0",
+            "error": { "type": "INVALID_MANIFEST", "content": { "reason": "bad" } }
+        }));
+        let failed = req.datastore_package_event().unwrap().unwrap();
+        assert_eq!(failed.version.as_deref(), Some("1.0"));
+        assert_eq!(serde_json::to_value(&failed).unwrap()["version"], json!("1.0"));
+        assert_eq!(failed.error.unwrap().error_type, "INVALID_MANIFEST");
+
+        let req = event("Alexa.DataStore.PackageManager.UsagesRemoved", json!({ "packageId": "scores" }));
+        assert_eq!(req.request.request_type, RequestType::DataStoreUsagesRemoved);
+        assert!(req.datastore_package_event().unwrap().is_ok());
+        assert!(event("Reminders.ReminderCreated", json!({})).datastore_package_event().is_none());
+    }
+
+    #[test]
+    fn test_missing_context() {
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "request": {
+                "type": "Messaging.MessageReceived",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US",
+                "message": { "order": "shipped" }
+            }
+        })).unwrap();
+        assert_eq!(req.request.request_type, RequestType::Other(String::from("Messaging.MessageReceived")));
+        assert!(req.context.system.api_access_token.is_none());
+        assert!(req.context.extra.is_empty());
+        assert_eq!(req.user_id(), None);
+        assert_eq!(req.device_id(), None);
+    }
+
+    fn assert_sessionless(req: &RequestEnvelope) {
+        assert!(!req.has_session());
+        assert!(!req.is_new());
+        assert_eq!(req.attribute_value("lastSpeech"), None);
+        assert_eq!(req.intent_type(), None);
+        assert_eq!(req.slot_value("name"), None);
+        assert_eq!(req.locale(), Some(&locale!(English, USA)));
+    }
+
+    fn playback_controller_req(request_type: &str) -> serde_json::Value {
+        json!({
+            "version": "1.0",
+            "context": {
+                "AudioPlayer": {
+                    "token": "track-1",
+                    "offsetInMilliseconds": 1000,
+                    "playerActivity": "PLAYING"
+                },
+                "System": {
+                    "application": {
+                        "applicationId": "amzn1.ask.skill.myappid"
+                    },
+                    "user": {
+                        "userId": "amzn1.ask.account.theuserid"
+                    },
+                    "device": {
+                        "deviceId": "amzn1.ask.device.superfakedevice",
+                        "supportedInterfaces": {
+                            "AudioPlayer": {}
+                        }
+                    },
+                    "apiEndpoint": "https://api.amazonalexa.com",
+                    "apiAccessToken": "53kr14t.k3y.d4t4-otherstuff"
+                }
+            },
+            "request": {
+                "type": request_type,
+                "requestId": "amzn1.echo-api.request.REQUEST",
+                "timestamp": "2025-03-17T23:27:29Z",
+                "locale": "en-US"
+            }
+        })
+    }
+
+    fn exception_encountered_req() -> serde_json::Value {
+        json!({
+            "version": "1.0",
+            "context": {
+                "System": {
+                    "application": {
+                        "applicationId": "amzn1.ask.skill.myappid"
+                    },
+                    "user": {
+                        "userId": "amzn1.ask.account.theuserid"
+                    },
+                    "device": {
+                        "deviceId": "amzn1.ask.device.superfakedevice",
+                        "supportedInterfaces": {}
+                    },
+                    "apiEndpoint": "https://api.amazonalexa.com",
+                    "apiAccessToken": "53kr14t.k3y.d4t4-otherstuff"
+                }
+            },
+            "request": {
+                "type": "System.ExceptionEncountered",
+                "requestId": "amzn1.echo-api.request.REQUEST",
+                "timestamp": "2025-03-17T23:27:29Z",
+                "locale": "en-US",
+                "error": {
+                    "type": "INVALID_RESPONSE",
+                    "message": "An exception occurred while dispatching the request to the skill."
+                },
+                "cause": {
+                    "requestId": "amzn1.echo-api.request.CAUSE"
+                }
+            }
+        })
+    }
+
     fn default_spanish_req() -> serde_json::Value {
         json!({
             "version": "1.0",
@@ -523,6 +2693,14 @@ mod tests {
         })
     }
 
+    /// Builds an envelope from the fields of its `System` context and of its `request`, which needs only a
+    /// `type`: the version, request id and timestamp are filled in.
+    fn envelope(system: serde_json::Value, request: serde_json::Value) -> RequestEnvelope {
+        let mut fields = json!({ "requestId": "amzn1.echo-api.request.id", "timestamp": "2018-12-08T05:37:32Z" });
+        fields.as_object_mut().unwrap().extend(request.as_object().unwrap().clone());
+        serde_json::from_value(json!({ "version": "1.0", "context": { "System": system }, "request": fields })).unwrap()
+    }
+
     fn default_req() -> serde_json::Value {
         json!({
             "version": "1.0",
@@ -1,14 +1,18 @@
 use serde::{Deserialize, Serialize};
 
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::time::Duration;
 
 use crate::declare_api_enum;
+use crate::request::{Language, Locale, RequestEnvelope};
 
 declare_api_enum! {
     Version {
         V1_0 => "1.0"
     }
 }
+#[allow(clippy::derivable_impls)]
 impl Default for Version {
     fn default() -> Self {
         Self::V1_0
@@ -19,27 +23,76 @@ impl ResponseEnvelope {
     /// Constructs a new response with only required elements
     pub fn new(should_end: bool) -> Self {
         let mut env = Self::default();
-        env.response.should_end_session = should_end;
+        env.response.should_end_session = Some(should_end);
         env
     }
 
     /// Constructs a basic plain response with a simple card
-    pub fn new_simple(title: &str, text: &str) -> Self {
+    pub fn new_simple(title: impl Into<String>, text: impl Into<String>) -> Self {
         Self::simple(title, text)
     }
 
-    /// Constructs a basic plain response with a simple card
-    pub fn simple(title: &str, text: &str) -> Self {
+    /// Constructs a basic plain response with a simple card, ending the session.
+    /// Chain [`ResponseEnvelope::keep_session`] to keep the session open instead, or see [`ResponseEnvelope::ask`]
+    pub fn simple(title: impl Into<String>, text: impl Into<String>) -> Self {
+        let text = text.into();
         Self::new(true)
-            .card(Card::simple(title, text))
+            .card(Card::simple(title, text.clone()))
             .speech(Speech::plain(text))
     }
 
+    /// Constructs a basic plain response with a simple card, keeping the session open for the user's answer
+    pub fn ask(title: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::simple(title, text).keep_session()
+    }
+
     /// Constructs an empty response ending the session
     pub fn end() -> Self {
         Self::new(true)
     }
 
+    /// Constructs a polite "something went wrong" response, with speech and a simple card, in the language
+    /// of the given locale, for use by error handlers. Falls back to English for unsupported languages or
+    /// a missing locale, e.g. `ResponseEnvelope::error_for_locale(req.locale())`.
+    pub fn error_for_locale(locale: Option<&Locale>) -> Self {
+        let (title, text) = match locale.map(|l| &l.language) {
+            Some(Language::German) => ("Fehler", "Entschuldigung, etwas ist schiefgelaufen. Bitte versuche es später noch einmal."),
+            Some(Language::Spanish) => ("Error", "Lo siento, algo salió mal. Por favor, inténtalo de nuevo más tarde."),
+            Some(Language::French) => ("Erreur", "Désolé, une erreur s'est produite. Veuillez réessayer plus tard."),
+            Some(Language::Italian) => ("Errore", "Mi dispiace, qualcosa è andato storto. Riprova più tardi."),
+            Some(Language::Japanese) => ("エラー", "申し訳ありません、問題が発生しました。後でもう一度お試しください。"),
+            Some(Language::Hindi) => ("त्रुटि", "क्षमा करें, कुछ गलत हो गया। कृपया बाद में फिर से प्रयास करें।"),
+            Some(Language::Portuguese) => ("Erro", "Desculpe, algo deu errado. Por favor, tente novamente mais tarde."),
+            _ => ("Error", "Sorry, something went wrong. Please try again later."),
+        };
+        Self::simple(title, text)
+    }
+
+    /// Constructs a response asking the user to link their account, with a link account card
+    /// and the given plain text speech
+    pub fn require_account_link(speech: impl Into<String>) -> Self {
+        Self::new(true)
+            .card(Card::link_account())
+            .speech(Speech::plain(speech))
+    }
+
+    /// sets the `shouldEndSession` flag of the response. `None` omits the flag entirely, which
+    /// is what AudioPlayer-only responses need in order to leave playback state untouched.
+    pub fn should_end_session(mut self, should_end: Option<bool>) -> Self {
+        self.response.should_end_session = should_end;
+        self
+    }
+
+    /// keeps the session open after this response, see [`ResponseEnvelope::should_end_session`]
+    pub fn keep_session(self) -> Self {
+        self.should_end_session(Some(false))
+    }
+
+    /// ends the session after this response, see [`ResponseEnvelope::should_end_session`]
+    pub fn end_session(self) -> Self {
+        self.should_end_session(Some(true))
+    }
+
     /// adds a speach element to the response
     pub fn speech(mut self, speech: Speech) -> Self {
         self.response.output_speech = Some(speech);
@@ -52,29 +105,212 @@ impl ResponseEnvelope {
         self
     }
 
+    /// adds a reprompt speech element to the response, keeping any reprompt directives
+    pub fn reprompt(mut self, speech: Speech) -> Self {
+        self.response.reprompt.get_or_insert_with(Reprompt::default).output_speech = Some(speech);
+        self
+    }
+
+    /// adds a directive to the reprompt of this response, such as an APLA `RenderDocument`
+    /// directive for an audio-rich reprompt
+    pub fn reprompt_with_directive(mut self, directive: Directive) -> Self {
+        let reprompt = self.response.reprompt.get_or_insert_with(Reprompt::default);
+        match reprompt.directives.as_mut() {
+            Some(vec) => vec.push(directive),
+            None => reprompt.directives = Some(vec![directive]),
+        };
+        self
+    }
+
     /// adds an attribute key/value pair to the response
     /// attributes can be read on the next request for basic state
     /// persistance
-    pub fn add_attribute(&mut self, key: &str, val: &str) {
+    pub fn add_attribute(&mut self, key: impl Into<String>, val: impl Into<String>) {
         if let Some(ref mut h) = self.session_attributes {
-            let _ = h.insert(String::from(key), String::from(val));
+            let _ = h.insert(key.into(), val.into());
         } else {
             let mut h = HashMap::new();
-            h.insert(String::from(key), String::from(val));
+            h.insert(key.into(), val.into());
             self.session_attributes = Some(h)
         }
     }
 
-    pub fn add_directive(&mut self, directive: Directive) {
-        match self.response.directives.as_mut() {
-            Some(vec) => vec.push(directive),
-            None => self.response.directives = Some(vec![directive]),
+    /// Constructs a plain response speaking at most `max_chars` characters of `text`. When the text is
+    /// longer, `prompt` (e.g. "would you like me to continue?") is spoken after the first part, the session is kept
+    /// open and the remainder is stored in the [`CONTINUATION_ATTRIBUTE`] session attribute, for the next turn to
+    /// continue from with another call to this function.
+    pub fn continued(text: &str, max_chars: usize, prompt: &str) -> Self {
+        match split_speech(text, max_chars) {
+            (head, None) => Self::new(true).speech(Speech::plain(head)),
+            (head, Some(rest)) => {
+                let mut env = Self::new(false)
+                    .speech(Speech::plain(format!("{} {}", head, prompt)))
+                    .reprompt(Speech::plain(prompt));
+                env.add_attribute(CONTINUATION_ATTRIBUTE, rest);
+                env
+            }
+        }
+    }
+
+    /// saves the output speech of this response in the [`LAST_SPEECH_ATTRIBUTE`] session attribute,
+    /// so that an `AMAZON.RepeatIntent` on the next turn can replay it with [`ResponseEnvelope::repeat_from`]
+    pub fn remember_speech(mut self) -> Self {
+        if let Some(json) = self.response.output_speech.as_ref().and_then(|s| serde_json::to_string(s).ok()) {
+            self.add_attribute(LAST_SPEECH_ATTRIBUTE, &json);
+        }
+        self
+    }
+
+    /// Constructs a response repeating the speech saved by [`ResponseEnvelope::remember_speech`] in the request's
+    /// session, keeping the session open and the speech saved for a further repeat. Other session attributes are
+    /// carried over. A saved value which isn't JSON is repeated as plain text. Returns `None` if no speech was saved.
+    pub fn repeat_from(req: &RequestEnvelope) -> Option<Self> {
+        let saved = req.attribute_value(LAST_SPEECH_ATTRIBUTE)?;
+        let speech = serde_json::from_str(saved).unwrap_or_else(|_| Speech::plain(saved));
+        let mut env = Self::new(false).speech(speech);
+        env.echo_attributes(req);
+        Some(env)
+    }
+
+    /// removes an attribute from the response, returning its value if it was set
+    pub fn remove_attribute(&mut self, key: &str) -> Option<String> {
+        self.session_attributes.as_mut().and_then(|h| h.remove(key))
+    }
+
+    /// removes all attributes from the response, so the next request carries no session state
+    pub fn clear_attributes(&mut self) {
+        self.session_attributes = None;
+    }
+
+    /// replaces all attributes of the response with the given key/value pairs
+    pub fn set_attributes(&mut self, attributes: HashMap<String, String>) {
+        self.session_attributes = Some(attributes);
+    }
+
+    /// copies the session attributes of the request into this response. Attributes which
+    /// have already been set on the response take precedence over the request's values.
+    pub fn echo_attributes(&mut self, req: &RequestEnvelope) {
+        let Some(attributes) = req.session.as_ref().and_then(|s| s.attributes.as_ref()) else {
+            return;
         };
+        let h = self.session_attributes.get_or_insert_with(HashMap::new);
+        for (key, val) in attributes {
+            h.entry(key.clone()).or_insert_with(|| val.clone());
+        }
+    }
+
+    /// records that the treatment of the experiment `id` affected this response, so that Alexa counts the
+    /// user in the experiment's metrics. Call it whenever the skill behaves according to its treatment:
+    /// ```
+    /// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+    /// #     "version": "1.0",
+    /// #     "context": { "System": {}, "Experimentation": { "activeExperiments": [{ "id": "exp-1", "treatmentId": "T1" }] } },
+    /// #     "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+    /// # })).unwrap();
+    /// use alexa_sdk::ResponseEnvelope;
+    ///
+    /// let mut res = ResponseEnvelope::simple("hello", "hello world");
+    /// if req.experiment_treatment("exp-1") == Some("T1") {
+    ///     res = ResponseEnvelope::simple("hello", "hello, and welcome");
+    ///     res.trigger_experiment("exp-1");
+    /// }
+    /// ```
+    pub fn trigger_experiment(&mut self, id: &str) {
+        let triggered = &mut self.response.experimentation.get_or_insert_with(TriggeredExperiments::default).triggered_experiments;
+        if !triggered.iter().any(|t| t == id) {
+            triggered.push(String::from(id));
+        }
+    }
+
+    /// adds a directive to the response. A directive which breaks the limits checked by
+    /// [`ResponseEnvelope::try_add_directive`] panics in debug builds, and is still added in release builds,
+    /// where [`ResponseEnvelope::validate`] reports it.
+    pub fn add_directive(&mut self, directive: Directive) {
+        if let Err(e) = self.check_directive(&directive) {
+            debug_assert!(false, "{}", e);
+        }
+        self.push_directive(directive);
+    }
+
+    /// adds a directive to the response, unless the response already has [`MAX_DIRECTIVES`] directives,
+    /// or the directive is a second `AudioPlayer.Play` directive. `strictness` decides whether breaking
+    /// these limits returns the error or panics.
+    pub fn try_add_directive(&mut self, directive: Directive, strictness: Strictness) -> Result<(), ValidationError> {
+        if let Err(e) = self.check_directive(&directive) {
+            match strictness {
+                Strictness::Panic => panic!("{}", e),
+                Strictness::DebugPanic => debug_assert!(false, "{}", e),
+                Strictness::Lenient => {},
+            }
+            return Err(e);
+        }
+        self.push_directive(directive);
+        Ok(())
+    }
+
+    fn push_directive(&mut self, directive: Directive) {
+        self.response.directives.get_or_insert_with(Vec::new).push(directive);
+    }
+
+    fn check_directive(&self, directive: &Directive) -> Result<(), ValidationError> {
+        let directives = self.response.directives.as_deref().unwrap_or_default();
+        if directives.len() >= MAX_DIRECTIVES {
+            return Err(ValidationError::TooManyDirectives(directives.len() + 1));
+        }
+        if directive.is_play() && directives.iter().any(Directive::is_play) {
+            return Err(ValidationError::MultiplePlayDirectives);
+        }
+        Ok(())
+    }
+
+    /// removes any directives belonging to an interface which the requesting device doesn't support,
+    /// as declared in its `supportedInterfaces`. See [`Directive::interface`].
+    pub fn prune_unsupported(&mut self, req: &RequestEnvelope) {
+        if let Some(vec) = self.response.directives.as_mut() {
+            vec.retain(|d| d.interface().is_none_or(|i| req.supports_interface(i)));
+            if vec.is_empty() {
+                self.response.directives = None;
+            }
+        }
+    }
+
+    /// Renders `document` with `datasources` when the requesting device supports APL, and leaves the response
+    /// unchanged otherwise, so the same response works on devices with and without screens. The document is
+    /// identified by `token`, which the skill keeps to recognize the document's `UserEvent`s and to send it
+    /// commands, e.g. one generated with [`crate::apl::AplToken::generate`]:
+    /// ```
+    /// # let req: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+    /// #     "version": "1.0",
+    /// #     "context": { "System": { "device": { "deviceId": "id", "supportedInterfaces": { "Alexa.Presentation.APL": {} } } } },
+    /// #     "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+    /// # })).unwrap();
+    /// use alexa_sdk::apl::AplToken;
+    /// use alexa_sdk::ResponseEnvelope;
+    ///
+    /// let token = AplToken::generate("document");
+    /// let res = ResponseEnvelope::simple("hello", "hello world")
+    ///     .with_apl(&req, &token, serde_json::json!({ "type": "APL", "version": "2023.3" }), None);
+    /// assert_eq!(res.response.directives.unwrap().len(), 1);
+    /// ```
+    #[cfg(feature = "apl")]
+    pub fn with_apl<C: crate::request::RequestContext>(
+        mut self,
+        req: &RequestEnvelope<C>,
+        token: &crate::apl::AplToken,
+        document: serde_json::Value,
+        datasources: Option<HashMap<String, crate::apl::DataSource>>,
+    ) -> Self {
+        if req.supports_interface("Alexa.Presentation.APL") {
+            let mut directive = crate::apl::RenderDocumentDirective::new(token.as_str(), document);
+            directive.datasources = datasources;
+            self.push_directive(directive.into());
+        }
+        self
     }
 }
 
 /// Response struct implementing the [Alexa JSON spec](https://developer.amazon.com/docs/custom-skills/request-and-response-json-reference.html#response-parameters)
-#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+#[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseEnvelope {
     pub version: Version,
@@ -83,8 +319,21 @@ pub struct ResponseEnvelope {
     pub response: Response,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// How [`ResponseEnvelope::try_add_directive`] reacts to a directive which breaks the directive limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strictness {
+    /// return the error without adding the directive
+    Lenient,
+    /// panic in debug builds, and behave like `Lenient` in release builds
+    #[default]
+    DebugPanic,
+    /// always panic
+    Panic,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_speech: Option<Speech>,
@@ -92,24 +341,39 @@ pub struct Response {
     pub card: Option<Card>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reprompt: Option<Reprompt>,
-    pub should_end_session: bool,
+    /// Omitted from the response when `None`, which is required for some AudioPlayer-only
+    /// responses. Defaults to `Some(true)`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub should_end_session: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directives: Option<Vec<Directive>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub directives: Option<Vec<Directive>>
+    pub experimentation: Option<TriggeredExperiments>,
 }
 impl Default for Response {
     fn default() -> Self {
         Self {
-            should_end_session: true,
+            should_end_session: Some(true),
             output_speech: None,
             card: None,
             reprompt: None,
             directives: None,
+            experimentation: None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// The skill experiments whose treatment affected a response, see [`ResponseEnvelope::trigger_experiment`]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct TriggeredExperiments {
+    pub triggered_experiments: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)]
 pub enum Directive {
     #[cfg(feature = "audioplayer")]
     #[serde(rename = "AudioPlayer.Play")]
@@ -119,10 +383,95 @@ pub enum Directive {
     #[serde(rename = "AudioPlayer.Stop")]
     Stop,
 
+    #[cfg(feature = "advertisement")]
+    #[serde(rename = "Alexa.Advertisement.InjectAds")]
+    InjectAds(crate::advertisement::InjectAdsDirective),
+
+    #[cfg(feature = "dialog")]
+    #[serde(rename = "Dialog.DelegateRequest")]
+    DelegateRequest(crate::dialog::DelegateRequestDirective),
+
+    #[cfg(feature = "dialog")]
+    #[serde(rename = "Dialog.UpdateDynamicEntities")]
+    UpdateDynamicEntities(crate::dialog::UpdateDynamicEntitiesDirective),
+
+    #[cfg(feature = "apl")]
+    #[serde(rename = "Alexa.Presentation.APL.RenderDocument")]
+    RenderDocument(crate::apl::RenderDocumentDirective),
+
+    #[cfg(feature = "apl")]
+    #[serde(rename = "Alexa.Presentation.APL.ExecuteCommands")]
+    ExecuteCommands(crate::apl::ExecuteCommandsDirective),
+
+    #[cfg(feature = "apl")]
+    #[serde(rename = "Alexa.Presentation.APL.SendTokenListData")]
+    SendTokenListData(crate::apl::SendTokenListDataDirective),
+
+    #[cfg(feature = "apl")]
+    #[serde(rename = "Alexa.Presentation.APLT.RenderDocument")]
+    AplTRenderDocument(crate::apl::AplTRenderDocumentDirective),
+
+    #[cfg(feature = "apl")]
+    #[serde(rename = "Alexa.Presentation.APLT.ExecuteCommands")]
+    AplTExecuteCommands(crate::apl::AplTExecuteCommandsDirective),
+
+    #[cfg(feature = "monetization")]
+    #[serde(rename = "Connections.SendRequest")]
+    SendRequest(crate::monetization::SendRequestDirective),
+
     #[serde(untagged)]
     Other(serde_json::Value)
 }
 
+/// Device interfaces which are only available when listed in the device's `supportedInterfaces`
+const DEVICE_INTERFACES: [&str; 6] = [
+    "AudioPlayer",
+    "Display",
+    "VideoApp",
+    "Alexa.Presentation.APL",
+    "Alexa.Presentation.APLT",
+    "Alexa.Presentation.HTML",
+];
+
+impl Directive {
+    fn is_play(&self) -> bool {
+        #[cfg(feature = "audioplayer")]
+        if let Directive::Play(_) = self {
+            return true;
+        }
+        matches!(self, Directive::Other(v) if v.get("type").and_then(serde_json::Value::as_str) == Some("AudioPlayer.Play"))
+    }
+
+    /// returns the device interface which must be supported by the requesting device in order to
+    /// accept this directive, or `None` if the directive can be sent to any device.
+    pub fn interface(&self) -> Option<&str> {
+        match self {
+            #[cfg(feature = "audioplayer")]
+            Directive::Play(_) | Directive::Stop => Some("AudioPlayer"),
+
+            #[cfg(feature = "apl")]
+            Directive::RenderDocument(_) | Directive::ExecuteCommands(_) | Directive::SendTokenListData(_) => Some("Alexa.Presentation.APL"),
+
+            #[cfg(feature = "apl")]
+            Directive::AplTRenderDocument(_) | Directive::AplTExecuteCommands(_) => Some("Alexa.Presentation.APLT"),
+
+            #[cfg(feature = "advertisement")]
+            Directive::InjectAds(_) => None,
+
+            #[cfg(feature = "dialog")]
+            Directive::DelegateRequest(_) | Directive::UpdateDynamicEntities(_) => None,
+
+            #[cfg(feature = "monetization")]
+            Directive::SendRequest(_) => None,
+
+            Directive::Other(value) => {
+                let (interface, _) = value.get("type")?.as_str()?.rsplit_once('.')?;
+                DEVICE_INTERFACES.into_iter().find(|i| *i == interface)
+            }
+        }
+    }
+}
+
 declare_api_enum! {
     SpeechType {
         PlainText,
@@ -137,8 +486,9 @@ declare_api_enum! {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Speech {
     #[serde(rename = "type")]
     pub speech_type: SpeechType,
@@ -152,25 +502,44 @@ pub struct Speech {
 
 impl Speech {
     /// Constructs a plain text output speech
-    pub fn plain(s: &str) -> Speech {
+    pub fn plain(s: impl Into<String>) -> Speech {
         Speech {
             speech_type: SpeechType::PlainText,
-            text: Some(String::from(s)),
+            text: Some(s.into()),
             ssml: None,
             play_behavior: None,
         }
     }
 
     /// Constructs an SSML output speech (with supplied SSML)
-    pub fn ssml(s: &str) -> Speech {
+    pub fn ssml(s: impl Into<String>) -> Speech {
         Speech {
             speech_type: SpeechType::SSML,
-            ssml: Some(String::from(s)),
+            ssml: Some(s.into()),
             text: None,
             play_behavior: None,
         }
     }
 
+    /// Returns the text or SSML of this speech, according to its type
+    pub fn content(&self) -> Option<&String> {
+        match self.speech_type {
+            SpeechType::SSML => self.ssml.as_ref(),
+            _ => self.text.as_ref(),
+        }
+    }
+
+    /// Estimates how long Alexa will take to speak this speech, at [`WORDS_PER_MINUTE`].
+    /// SSML tags are ignored, so breaks and prosody changes are not accounted for.
+    pub fn estimated_duration(&self) -> Duration {
+        let content = self.content().map(String::as_str).unwrap_or_default();
+        let words = match self.speech_type {
+            SpeechType::SSML => strip_tags(content).split_whitespace().count(),
+            _ => content.split_whitespace().count(),
+        };
+        Duration::from_millis(words as u64 * 60_000 / WORDS_PER_MINUTE)
+    }
+
     /// Adds play behavior to a speech object
     pub fn play_behavior(&mut self, behavior: PlayBehavior) {
         self.play_behavior = Some(behavior);
@@ -186,7 +555,8 @@ declare_api_enum! {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct Card {
     #[serde(rename = "type")]
     pub card_type: CardType,
@@ -204,11 +574,11 @@ pub struct Card {
 
 impl Card {
     /// Constructs a simple card for an Alexa repsonse object
-    pub fn simple(title: &str, text: &str) -> Card {
+    pub fn simple(title: impl Into<String>, text: impl Into<String>) -> Card {
         Card {
             card_type: CardType::Simple,
-            title: Some(String::from(title)),
-            content: Some(String::from(text)),
+            title: Some(title.into()),
+            content: Some(text.into()),
             text: None,
             image: None,
             permissions: None,
@@ -216,12 +586,12 @@ impl Card {
     }
 
     /// Constructs a standard card for an Alexa response object
-    pub fn standard(title: &str, text: &str, image: Image) -> Card {
+    pub fn standard(title: impl Into<String>, text: impl Into<String>, image: Image) -> Card {
         Card {
             card_type: CardType::Standard,
-            title: Some(String::from(title)),
+            title: Some(title.into()),
             content: None,
-            text: Some(String::from(text)),
+            text: Some(text.into()),
             image: Some(image),
             permissions: None,
         }
@@ -252,14 +622,27 @@ impl Card {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Reprompt object, from [the specification](https://developer.amazon.com/en-US/docs/alexa/custom-skills/request-and-response-json-reference.html#reprompt-object).
+/// A reprompt may carry speech, directives (currently only `Alexa.Presentation.APLA.RenderDocument`), or both.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Reprompt {
-    pub output_speech: Speech,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_speech: Option<Speech>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directives: Option<Vec<Directive>>,
+}
+impl Reprompt {
+    /// Constructs a reprompt with the given speech
+    pub fn new(speech: Speech) -> Self {
+        Self { output_speech: Some(speech), directives: None }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Image {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub small_image_url: Option<String>,
@@ -272,38 +655,394 @@ impl Image {
         Image::default()
     }
 
-    pub fn small_image_url(mut self, url: String) -> Self {
-        self.small_image_url = Some(url);
+    pub fn small_image_url(mut self, url: impl Into<String>) -> Self {
+        self.small_image_url = Some(url.into());
         self
     }
 
-    pub fn large_image_url(mut self, url: String) -> Self {
-        self.large_image_url = Some(url);
+    pub fn large_image_url(mut self, url: impl Into<String>) -> Self {
+        self.large_image_url = Some(url.into());
         self
     }
+
+    /// Derives both image urls from one naming pattern, in which `{base}` is replaced by `base`, `{size}` by
+    /// `small` or `large`, and `{width}` and `{height}` by the recommended dimensions of each image,
+    /// [`SMALL_IMAGE_SIZE`] and [`LARGE_IMAGE_SIZE`]. Both urls are validated:
+    /// ```
+    /// use alexa_sdk::response::Image;
+    ///
+    /// let image = Image::from_base_url("https://cdn.example.com/cards/pizza", "{base}-{width}x{height}.png").unwrap();
+    /// assert_eq!(image.small_image_url.unwrap(), "https://cdn.example.com/cards/pizza-720x480.png");
+    /// assert_eq!(image.large_image_url.unwrap(), "https://cdn.example.com/cards/pizza-1200x800.png");
+    /// ```
+    pub fn from_base_url(base: &str, pattern: &str) -> Result<Image, ImageUrlError> {
+        let url = |size: &str, (width, height): (u32, u32)| pattern
+            .replace("{base}", base)
+            .replace("{size}", size)
+            .replace("{width}", &width.to_string())
+            .replace("{height}", &height.to_string());
+        let image = Image::new()
+            .small_image_url(url("small", SMALL_IMAGE_SIZE))
+            .large_image_url(url("large", LARGE_IMAGE_SIZE));
+        image.validate()?;
+        Ok(image)
+    }
+
+    /// Checks both image urls with [`validate_image_url`]. Alexa recommends 720x480 pixels for the
+    /// small image and 1200x800 pixels for the large image.
+    pub fn validate(&self) -> Result<(), ImageUrlError> {
+        [&self.small_image_url, &self.large_image_url].into_iter()
+            .flatten()
+            .try_for_each(|url| validate_image_url(url))
+    }
 }
 
-impl Default for Image {
-    fn default() -> Self {
-        Image {
-            small_image_url: None,
-            large_image_url: None,
+/// Recommended width and height in pixels of the small image of a card
+pub const SMALL_IMAGE_SIZE: (u32, u32) = (720, 480);
+
+/// Recommended width and height in pixels of the large image of a card
+pub const LARGE_IMAGE_SIZE: (u32, u32) = (1200, 800);
+
+/// Maximum length of an image url
+pub const MAX_IMAGE_URL_CHARS: usize = 2000;
+
+/// A reason an image url would be rejected by Alexa devices, see [`validate_image_url`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageUrlError {
+    Empty,
+    /// image urls must use https
+    NotHttps(String),
+    /// the url exceeds [`MAX_IMAGE_URL_CHARS`]
+    TooLong(usize),
+}
+impl Display for ImageUrlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "image url is empty"),
+            Self::NotHttps(url) => write!(f, "image url {} does not use https", url),
+            Self::TooLong(n) => write!(f, "image url is {} characters, the maximum is {}", n, MAX_IMAGE_URL_CHARS),
+        }
+    }
+}
+impl std::error::Error for ImageUrlError {}
+
+/// Checks that an image url can be fetched by Alexa devices. Images which fail these checks are
+/// silently left out of cards and templates.
+pub fn validate_image_url(url: &str) -> Result<(), ImageUrlError> {
+    if url.is_empty() {
+        return Err(ImageUrlError::Empty);
+    }
+    if !url.get(..8).is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://")) {
+        return Err(ImageUrlError::NotHttps(String::from(url)));
+    }
+    match url.chars().count() {
+        n if n > MAX_IMAGE_URL_CHARS => Err(ImageUrlError::TooLong(n)),
+        _ => Ok(()),
+    }
+}
+
+/// Maximum number of characters in the text or SSML of an output speech or reprompt
+pub const MAX_SPEECH_CHARS: usize = 8000;
+
+/// Typical speaking rate of Alexa, used by [`Speech::estimated_duration`]
+pub const WORDS_PER_MINUTE: u64 = 150;
+
+/// Session attribute holding the output speech saved by [`ResponseEnvelope::remember_speech`], as JSON
+pub const LAST_SPEECH_ATTRIBUTE: &str = "alexa_sdk.lastSpeech";
+
+/// Session attribute holding the unspoken remainder of a speech split by [`ResponseEnvelope::continued`]
+pub const CONTINUATION_ATTRIBUTE: &str = "continuation";
+
+/// Maximum number of characters in the title and text of a card, combined
+pub const MAX_CARD_CHARS: usize = 8000;
+
+/// Maximum number of directives in a response, see [`ResponseEnvelope::try_add_directive`]
+pub const MAX_DIRECTIVES: usize = 10;
+
+/// Maximum size, in bytes, of a serialized response
+pub const MAX_RESPONSE_BYTES: usize = 24 * 1024;
+
+fn strip_tags(ssml: &str) -> String {
+    let mut text = String::with_capacity(ssml.len());
+    let mut in_tag = false;
+    for c in ssml.chars() {
+        match c {
+            '<' => { in_tag = true; text.push(' ') },
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {},
+        }
+    }
+    text
+}
+
+/// Splits plain text into a head of at most `max_chars` characters and the remainder, if any.
+/// The split is made at the last sentence end within the limit, falling back to the last word break.
+/// A `max_chars` of 0 is treated as 1, so that the head of a non-empty text is never empty.
+pub fn split_speech(text: &str, max_chars: usize) -> (&str, Option<&str>) {
+    let text = text.trim();
+    let limit = match text.char_indices().nth(max_chars.max(1)) {
+        Some((i, _)) => i,
+        None => return (text, None),
+    };
+    let window = &text[..limit];
+    let sentence_end = window.char_indices()
+        .rev()
+        .find(|&(i, c)| matches!(c, '.' | '!' | '?') && text[i + 1..].starts_with(char::is_whitespace))
+        .map(|(i, _)| i + 1);
+    let split = sentence_end
+        .or_else(|| window.rfind(char::is_whitespace))
+        .filter(|&i| i > 0)
+        .unwrap_or(limit);
+    let (head, rest) = text.split_at(split);
+    (head.trim_end(), Some(rest.trim_start()))
+}
+
+/// A problem found by [`ResponseEnvelope::validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// a `PlainText` speech has no `text`, or an `SSML` speech has no `ssml`
+    MissingSpeechContent(SpeechType),
+    /// the output speech exceeds [`MAX_SPEECH_CHARS`]
+    SpeechTooLong(usize),
+    /// the reprompt speech exceeds [`MAX_SPEECH_CHARS`]
+    RepromptTooLong(usize),
+    /// the card title and text exceed [`MAX_CARD_CHARS`]
+    CardTooLong(usize),
+    /// a reprompt is set on a response which ends the session, so it will never be spoken
+    RepromptWithEndedSession,
+    /// the serialized response exceeds [`MAX_RESPONSE_BYTES`]
+    ResponseTooLarge(usize),
+    /// the response has more than [`MAX_DIRECTIVES`] directives
+    TooManyDirectives(usize),
+    /// the response has more than one `AudioPlayer.Play` directive
+    MultiplePlayDirectives,
+    /// the captions of an `AudioPlayer.Play` directive lack a type or content, or have a type other than `WEBVTT`
+    InvalidCaptionData,
+    /// a `Connections.SendRequest` directive is sent with `shouldEndSession` set to `false`, which Alexa
+    /// rejects since the session is handed over to the requested task
+    SendRequestWithOpenSession,
+}
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSpeechContent(t) => write!(f, "{:?} speech has no content", t),
+            Self::SpeechTooLong(n) => write!(f, "output speech is {} characters, the maximum is {}", n, MAX_SPEECH_CHARS),
+            Self::RepromptTooLong(n) => write!(f, "reprompt speech is {} characters, the maximum is {}", n, MAX_SPEECH_CHARS),
+            Self::CardTooLong(n) => write!(f, "card is {} characters, the maximum is {}", n, MAX_CARD_CHARS),
+            Self::RepromptWithEndedSession => write!(f, "reprompt is ignored when shouldEndSession is true"),
+            Self::ResponseTooLarge(n) => write!(f, "response is {} bytes, the maximum is {}", n, MAX_RESPONSE_BYTES),
+            Self::TooManyDirectives(n) => write!(f, "response has {} directives, the maximum is {}", n, MAX_DIRECTIVES),
+            Self::MultiplePlayDirectives => write!(f, "response has more than one AudioPlayer.Play directive"),
+            Self::InvalidCaptionData => write!(f, "caption data must have WEBVTT type and content"),
+            Self::SendRequestWithOpenSession => write!(f, "Connections.SendRequest requires shouldEndSession to be true or unset"),
         }
     }
 }
+impl std::error::Error for ValidationError {}
+
+impl ResponseEnvelope {
+    /// Starts building a response which is validated when it is built, see [`ResponseBuilder::build`]
+    pub fn builder() -> ResponseBuilder {
+        ResponseBuilder::default()
+    }
+
+    /// The size in bytes of this response when serialized, to compare with [`MAX_RESPONSE_BYTES`]
+    pub fn serialized_size(&self) -> usize {
+        serde_json::to_vec(self).map_or(0, |bytes| bytes.len())
+    }
+
+    /// The `type` and serialized size in bytes of each directive of this response, in order, to find out what
+    /// is consuming the [`MAX_RESPONSE_BYTES`] budget
+    pub fn directive_sizes(&self) -> Vec<(String, usize)> {
+        self.response.directives.iter().flatten()
+            .filter_map(|d| serde_json::to_value(d).ok())
+            .map(|v| {
+                let size = serde_json::to_vec(&v).map_or(0, |bytes| bytes.len());
+                let directive_type = v.get("type").and_then(serde_json::Value::as_str).unwrap_or_default();
+                (String::from(directive_type), size)
+            })
+            .collect()
+    }
+
+    /// Checks this response against the size limits and consistency rules of the Alexa service,
+    /// returning every problem found.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        let response = &self.response;
+
+        if let Some(speech) = &response.output_speech {
+            match speech.content() {
+                None => errors.push(ValidationError::MissingSpeechContent(speech.speech_type.clone())),
+                Some(c) if c.chars().count() > MAX_SPEECH_CHARS => errors.push(ValidationError::SpeechTooLong(c.chars().count())),
+                _ => (),
+            }
+        }
+
+        if let Some(reprompt) = &response.reprompt {
+            if let Some(speech) = &reprompt.output_speech {
+                match speech.content() {
+                    None => errors.push(ValidationError::MissingSpeechContent(speech.speech_type.clone())),
+                    Some(c) if c.chars().count() > MAX_SPEECH_CHARS => errors.push(ValidationError::RepromptTooLong(c.chars().count())),
+                    _ => (),
+                }
+            }
+            if response.should_end_session == Some(true) {
+                errors.push(ValidationError::RepromptWithEndedSession);
+            }
+        }
+
+        if let Some(card) = &response.card {
+            let length = [&card.title, &card.content, &card.text].into_iter()
+                .flatten()
+                .map(|s| s.chars().count())
+                .sum();
+            if length > MAX_CARD_CHARS {
+                errors.push(ValidationError::CardTooLong(length));
+            }
+        }
+
+        if let Some(directives) = &response.directives {
+            if directives.len() > MAX_DIRECTIVES {
+                errors.push(ValidationError::TooManyDirectives(directives.len()));
+            }
+            if directives.iter().filter(|d| d.is_play()).count() > 1 {
+                errors.push(ValidationError::MultiplePlayDirectives);
+            }
+            #[cfg(feature = "audioplayer")]
+            for directive in directives {
+                if let Directive::Play(play) = directive {
+                    if let Some(Err(e)) = play.audio_item.stream.caption_data.as_ref().map(|c| c.validate()) {
+                        errors.push(e);
+                    }
+                }
+            }
+            #[cfg(feature = "monetization")]
+            if response.should_end_session == Some(false) && directives.iter().any(|d| matches!(d, Directive::SendRequest(_))) {
+                errors.push(ValidationError::SendRequestWithOpenSession);
+            }
+        }
+
+        let size = self.serialized_size();
+        if size > MAX_RESPONSE_BYTES {
+            errors.push(ValidationError::ResponseTooLarge(size));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// A builder for [`ResponseEnvelope`] which validates the response when it is built:
+/// ```
+/// use alexa_sdk::ResponseEnvelope;
+/// use alexa_sdk::response::Speech;
+///
+/// let res = ResponseEnvelope::builder()
+///     .speech(Speech::plain("what next?"))
+///     .reprompt(Speech::plain("what would you like to do next?"))
+///     .should_end_session(Some(false))
+///     .build();
+///
+/// assert!(res.is_ok());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResponseBuilder {
+    env: ResponseEnvelope,
+}
+
+impl ResponseBuilder {
+    /// sets the output speech
+    pub fn speech(mut self, speech: Speech) -> Self {
+        self.env = self.env.speech(speech);
+        self
+    }
+
+    /// sets the card
+    pub fn card(mut self, card: Card) -> Self {
+        self.env = self.env.card(card);
+        self
+    }
+
+    /// sets the reprompt speech
+    pub fn reprompt(mut self, speech: Speech) -> Self {
+        self.env = self.env.reprompt(speech);
+        self
+    }
+
+    /// adds a directive. Directive limits are checked by [`ResponseBuilder::build`].
+    pub fn directive(mut self, directive: Directive) -> Self {
+        self.env.push_directive(directive);
+        self
+    }
+
+    /// adds a session attribute
+    pub fn attribute(mut self, key: impl Into<String>, val: impl Into<String>) -> Self {
+        self.env.add_attribute(key, val);
+        self
+    }
+
+    /// sets the `shouldEndSession` flag, see [`ResponseEnvelope::should_end_session`]
+    pub fn should_end_session(mut self, should_end: Option<bool>) -> Self {
+        self.env = self.env.should_end_session(should_end);
+        self
+    }
+
+    /// keeps the session open, see [`ResponseEnvelope::keep_session`]
+    pub fn keep_session(self) -> Self {
+        self.should_end_session(Some(false))
+    }
+
+    /// ends the session, see [`ResponseEnvelope::end_session`]
+    pub fn end_session(self) -> Self {
+        self.should_end_session(Some(true))
+    }
+
+    /// saves the output speech for repeating, see [`ResponseEnvelope::remember_speech`]
+    pub fn remember_speech(mut self) -> Self {
+        self.env = self.env.remember_speech();
+        self
+    }
+
+    /// validates and returns the response, see [`ResponseEnvelope::validate`]
+    pub fn build(self) -> Result<ResponseEnvelope, Vec<ValidationError>> {
+        self.env.validate().map(|_| self.env)
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use serde_json::json;
+    use super::*;
 
-    use crate::audioplayer::{AudioItem, PlayDirective, Stream};
+    #[test]
+    fn whole_response_equality() {
+        let res = ResponseEnvelope::simple("hello", "hello world").reprompt(Speech::plain("still there?"));
+        assert_eq!(res, ResponseEnvelope::simple("hello", "hello world").reprompt(Speech::plain("still there?")));
+        assert_ne!(res, ResponseEnvelope::simple("hello", "hello world"));
 
-    use super::*;
+        let round_trip: ResponseEnvelope = serde_json::from_value(serde_json::to_value(&res).unwrap()).unwrap();
+        assert_eq!(round_trip, res);
+    }
+
+    #[test]
+    fn test_trigger_experiment() {
+        let mut res = ResponseEnvelope::simple("hello", "hello world");
+        res.trigger_experiment("exp-1");
+        res.trigger_experiment("exp-1");
+        res.trigger_experiment("exp-2");
+        assert_eq!(
+            serde_json::to_value(&res).unwrap()["response"]["experimentation"],
+            serde_json::json!({ "triggeredExperiments": ["exp-1", "exp-2"] })
+        );
+    }
 
     #[test]
     fn default_response() {
         assert_eq!(
-            serde_json::to_value(&ResponseEnvelope::default()).unwrap(),
+            serde_json::to_value(ResponseEnvelope::default()).unwrap(),
             serde_json::to_value(&ResponseEnvelope {
                 version: Version::V1_0,
                 session_attributes: None,
@@ -311,9 +1050,10 @@ mod tests {
                     output_speech: None, 
                     card: None, 
                     reprompt: None, 
-                    should_end_session: true, 
-                    directives: None
-                }
+                    should_end_session: Some(true),
+                    directives: None,
+                    experimentation: None,
+                },
             }).unwrap()
         );
     }
@@ -401,6 +1141,67 @@ mod tests {
         assert_eq!(attr, "value");
     }
 
+    #[test]
+    fn test_builder_validation() {
+        let builder = ResponseEnvelope::builder()
+            .speech(Speech::plain("a".repeat(MAX_SPEECH_CHARS + 1)))
+            .reprompt(Speech {
+                speech_type: SpeechType::SSML,
+                text: Some(String::from("hello")),
+                ssml: None,
+                play_behavior: None,
+            })
+            .card(Card::simple("title", "b".repeat(MAX_CARD_CHARS)))
+            .directive(Directive::Other(serde_json::json!({
+                "type": "Alexa.Presentation.APL.RenderDocument",
+                "document": "c".repeat(MAX_RESPONSE_BYTES)
+            })));
+        let size = serde_json::to_vec(&builder.env).unwrap().len();
+
+        assert_eq!(
+            Err(vec![
+                ValidationError::SpeechTooLong(MAX_SPEECH_CHARS + 1),
+                ValidationError::MissingSpeechContent(SpeechType::SSML),
+                ValidationError::RepromptWithEndedSession,
+                ValidationError::CardTooLong(MAX_CARD_CHARS + 5),
+                ValidationError::ResponseTooLarge(size),
+            ]),
+            builder.build().map(|_| ())
+        );
+
+        let res = ResponseEnvelope::builder()
+            .speech(Speech::plain("hello"))
+            .attribute("state", "greeted")
+            .build()
+            .unwrap();
+        assert_eq!(res.session_attributes.unwrap()["state"], "greeted");
+    }
+
+    #[test]
+    fn test_image_validation() {
+        assert_eq!(Ok(()), Image::new().small_image_url(String::from("HTTPS://localhost/small.png")).validate());
+        assert_eq!(
+            Err(ImageUrlError::NotHttps(String::from("http://localhost/large.png"))),
+            Image::new().large_image_url(String::from("http://localhost/large.png")).validate()
+        );
+        assert_eq!(Err(ImageUrlError::Empty), validate_image_url(""));
+        assert_eq!(
+            Err(ImageUrlError::TooLong(MAX_IMAGE_URL_CHARS + 1)),
+            validate_image_url(&format!("https://{}", "a".repeat(MAX_IMAGE_URL_CHARS - 7)))
+        );
+    }
+
+    #[test]
+    fn test_image_from_base_url() {
+        let image = Image::from_base_url("https://cdn.example.com/cards/pizza", "{base}/{size}.jpg").unwrap();
+        assert_eq!(image.small_image_url.unwrap(), "https://cdn.example.com/cards/pizza/small.jpg");
+        assert_eq!(image.large_image_url.unwrap(), "https://cdn.example.com/cards/pizza/large.jpg");
+        assert_eq!(
+            Err(ImageUrlError::NotHttps(String::from("http://cdn.example.com/pizza_720.png"))),
+            Image::from_base_url("http://cdn.example.com/pizza", "{base}_{width}.png")
+        );
+    }
+
     #[test]
     fn test_title() {
         let t = "hello, world";
@@ -420,16 +1221,295 @@ mod tests {
     #[test]
     fn test_should_end() {
         let r = ResponseEnvelope::simple("foo", "bar");
-        assert_eq!(r.response.should_end_session, true);
+        assert_eq!(r.response.should_end_session, Some(true));
+    }
+
+    #[test]
+    fn test_serialized_size() {
+        use serde_json::json;
+
+        let mut env = ResponseEnvelope::default();
+        assert_eq!(env.serialized_size(), serde_json::to_string(&env).unwrap().len());
+        assert!(env.directive_sizes().is_empty());
+
+        env.add_directive(Directive::Other(json!({"type": "Dialog.Delegate"})));
+        env.add_directive(Directive::Other(json!({"type": "Alexa.Presentation.APL.RenderDocument", "document": "x".repeat(100)})));
+        let sizes = env.directive_sizes();
+        assert_eq!(sizes[0], (String::from("Dialog.Delegate"), r#"{"type":"Dialog.Delegate"}"#.len()));
+        assert_eq!(sizes[1].0, "Alexa.Presentation.APL.RenderDocument");
+        assert!(sizes[1].1 > 100);
+    }
+
+    #[test]
+    fn test_repeat() {
+        let res = ResponseEnvelope::builder()
+            .speech(Speech::ssml("<speak>pick a number</speak>"))
+            .keep_session()
+            .remember_speech()
+            .attribute("state", "guessing")
+            .build()
+            .unwrap();
+
+        let mut req: RequestEnvelope = serde_json::from_value(serde_json::json!({
+            "version": "1.0",
+            "session": {
+                "new": false,
+                "sessionId": "amzn1.echo-api.session.abc123",
+                "application": { "applicationId": "amzn1.ask.skill.myappid" },
+                "user": { "userId": "amzn1.ask.account.theuserid" }
+            },
+            "context": { "System": {} },
+            "request": {
+                "type": "IntentRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US",
+                "intent": { "name": "AMAZON.RepeatIntent", "confirmationStatus": "NONE" }
+            }
+        })).unwrap();
+        assert!(ResponseEnvelope::repeat_from(&req).is_none());
+
+        req.session.as_mut().unwrap().attributes = res.session_attributes.clone();
+        let repeated = ResponseEnvelope::repeat_from(&req).unwrap();
+        assert_eq!(repeated.response.output_speech.as_ref().unwrap().ssml.as_ref().unwrap(), "<speak>pick a number</speak>");
+        assert_eq!(repeated.response.should_end_session, Some(false));
+        assert_eq!(repeated.session_attributes, res.session_attributes);
+    }
+
+    #[test]
+    fn test_directive_limits() {
+        use serde_json::json;
+
+        let play = || Directive::Other(json!({"type": "AudioPlayer.Play"}));
+        let mut env = ResponseEnvelope::default();
+        env.try_add_directive(play(), Strictness::Lenient).unwrap();
+        assert_eq!(env.try_add_directive(play(), Strictness::Lenient), Err(ValidationError::MultiplePlayDirectives));
+        for _ in 1..MAX_DIRECTIVES {
+            env.try_add_directive(Directive::Other(json!({"type": "Dialog.Delegate"})), Strictness::Lenient).unwrap();
+        }
+        assert_eq!(
+            env.try_add_directive(Directive::Other(json!({"type": "Dialog.Delegate"})), Strictness::Lenient),
+            Err(ValidationError::TooManyDirectives(MAX_DIRECTIVES + 1))
+        );
+        assert_eq!(env.response.directives.as_ref().unwrap().len(), MAX_DIRECTIVES);
+
+        let built = ResponseEnvelope::builder().directive(play()).directive(play()).build();
+        assert_eq!(built, Err(vec![ValidationError::MultiplePlayDirectives]));
+    }
+
+    #[test]
+    #[should_panic(expected = "more than one AudioPlayer.Play")]
+    fn test_directive_limits_panic() {
+        use serde_json::json;
+
+        let mut env = ResponseEnvelope::default();
+        env.add_directive(Directive::Other(json!({"type": "AudioPlayer.Play"})));
+        let _ = env.try_add_directive(Directive::Other(json!({"type": "AudioPlayer.Play"})), Strictness::Panic);
+    }
+
+    #[test]
+    fn test_error_for_locale() {
+        let r = ResponseEnvelope::error_for_locale(Some(&Locale::from("fr-CA")));
+        assert_eq!(r.response.card.unwrap().title.unwrap(), "Erreur");
+        assert_eq!(r.response.should_end_session, Some(true));
+
+        let r = ResponseEnvelope::error_for_locale(Some(&Locale::from("ar-SA")));
+        assert_eq!(r.response.output_speech.unwrap().text.unwrap(), "Sorry, something went wrong. Please try again later.");
+
+        let r = ResponseEnvelope::error_for_locale(None);
+        assert_eq!(r.response.card.unwrap().title.unwrap(), "Error");
+    }
+
+    #[test]
+    fn test_session_toggles() {
+        let r = ResponseEnvelope::simple("foo", "bar").keep_session();
+        assert_eq!(r.response.should_end_session, Some(false));
+        let r = r.end_session();
+        assert_eq!(r.response.should_end_session, Some(true));
+
+        let r = ResponseEnvelope::ask("foo", "bar");
+        assert_eq!(r.response.should_end_session, Some(false));
+        assert_eq!(r.response.card.unwrap().content.unwrap(), "bar");
+    }
+
+    #[test]
+    fn test_omit_should_end() {
+        let r = ResponseEnvelope::new(false).should_end_session(None);
+        assert_eq!(
+            serde_json::json!({
+                "version": "1.0",
+                "response": {}
+            }),
+            serde_json::to_value(r).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_require_account_link() {
+        let r = ResponseEnvelope::require_account_link("please link your account");
+        assert_eq!(r.response.card.unwrap().card_type, CardType::LinkAccount);
+        assert_eq!(r.response.output_speech.unwrap().text.unwrap(), "please link your account");
+        assert_eq!(r.response.should_end_session, Some(true));
+    }
+
+    #[test]
+    fn test_estimated_duration() {
+        let words = vec!["word"; 300].join(" ");
+        assert_eq!(Speech::plain(&words).estimated_duration(), Duration::from_secs(120));
+        let ssml = Speech::ssml("<speak>one<break time=\"1s\"/>two <emphasis>three</emphasis></speak>");
+        assert_eq!(ssml.estimated_duration(), Duration::from_millis(1200));
+    }
+
+    #[test]
+    fn test_split_speech() {
+        assert_eq!(split_speech("Short.", 100), ("Short.", None));
+        assert_eq!(split_speech("One. Two! Three?", 12), ("One. Two!", Some("Three?")));
+        assert_eq!(split_speech("no sentence ends here", 12), ("no sentence", Some("ends here")));
+        assert_eq!(split_speech("unbroken", 3), ("unb", Some("roken")));
+        assert_eq!(split_speech("ab", 0), ("a", Some("b")));
+        assert_eq!(split_speech("", 0), ("", None));
+        assert_eq!(split_speech("v1.2 is out. yes", 10), ("v1.2 is", Some("out. yes")));
+    }
+
+    #[test]
+    fn test_continued() {
+        let r = ResponseEnvelope::continued("First part. Second part.", 15, "Continue?");
+        assert_eq!(r.response.output_speech.unwrap().text.unwrap(), "First part. Continue?");
+        assert_eq!(r.response.should_end_session, Some(false));
+        assert_eq!(r.session_attributes.unwrap().get(CONTINUATION_ATTRIBUTE).unwrap(), "Second part.");
+
+        let r = ResponseEnvelope::continued("Second part.", 15, "Continue?");
+        assert_eq!(r.response.output_speech.unwrap().text.unwrap(), "Second part.");
+        assert_eq!(r.response.should_end_session, Some(true));
+        assert!(r.session_attributes.is_none());
     }
 
     #[test]
+    fn test_attribute_removal() {
+        let mut r = ResponseEnvelope::new(false);
+        assert_eq!(r.remove_attribute("state"), None);
+
+        r.add_attribute("state", "asking");
+        r.add_attribute("count", "1");
+        assert_eq!(r.remove_attribute("state"), Some(String::from("asking")));
+        assert_eq!(r.session_attributes.as_ref().unwrap().len(), 1);
+
+        r.set_attributes(HashMap::from([(String::from("state"), String::from("done"))]));
+        assert_eq!(r.session_attributes.as_ref().unwrap().get("count"), None);
+        assert_eq!(r.session_attributes.as_ref().unwrap().get("state").unwrap(), "done");
+
+        r.clear_attributes();
+        assert_eq!(serde_json::to_value(&r).unwrap().get("sessionAttributes"), None);
+    }
+
+    #[test]
+    fn test_echo_attributes() {
+        use serde_json::json;
+
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "session": {
+                "new": false,
+                "sessionId": "amzn1.echo-api.session.abc123",
+                "application": { "applicationId": "amzn1.ask.skill.myappid" },
+                "attributes": { "state": "asking", "count": "1" },
+                "user": { "userId": "amzn1.ask.account.theuserid" }
+            },
+            "context": { "System": {} },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US"
+            }
+        })).unwrap();
+
+        let mut res = ResponseEnvelope::new(false);
+        res.add_attribute("count", "2");
+        res.echo_attributes(&req);
+
+        let attrs = res.session_attributes.unwrap();
+        assert_eq!(attrs.get("state").unwrap(), "asking");
+        assert_eq!(attrs.get("count").unwrap(), "2");
+    }
+
+    #[test]
+    fn test_prune_unsupported() {
+        use serde_json::json;
+
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "context": {
+                "System": {
+                    "device": {
+                        "deviceId": "amzn1.ask.device.DEVICE",
+                        "supportedInterfaces": { "AudioPlayer": {} }
+                    }
+                }
+            },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US"
+            }
+        })).unwrap();
+
+        let mut res = ResponseEnvelope::new(false);
+        res.add_directive(Directive::Other(json!({"type": "Alexa.Presentation.APL.RenderDocument"})));
+        res.add_directive(Directive::Other(json!({"type": "AudioPlayer.ClearQueue"})));
+        res.add_directive(Directive::Other(json!({"type": "Dialog.Delegate"})));
+        res.add_directive(Directive::Other(json!({"type": "Display.RenderTemplate"})));
+        res.prune_unsupported(&req);
+
+        assert_eq!(
+            json!([
+                {"type": "AudioPlayer.ClearQueue"},
+                {"type": "Dialog.Delegate"}
+            ]),
+            serde_json::to_value(res.response.directives).unwrap()
+        );
+    }
+
+    #[test]
+    fn reprompt_with_directive() {
+        use serde_json::json;
+
+        let env = ResponseEnvelope::new(false)
+            .reprompt(Speech::plain("are you still there?"))
+            .reprompt_with_directive(Directive::Other(json!({
+                "type": "Alexa.Presentation.APLA.RenderDocument",
+                "token": "reprompt"
+            })));
+
+        assert_eq!(
+            json!({
+                "outputSpeech": {
+                    "type": "PlainText",
+                    "text": "are you still there?"
+                },
+                "directives": [
+                    {
+                        "type": "Alexa.Presentation.APLA.RenderDocument",
+                        "token": "reprompt"
+                    }
+                ]
+            }),
+            serde_json::to_value(env.response.reprompt).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "audioplayer")]
     fn default_with_directives() {
+        use serde_json::json;
+        use crate::audioplayer::{AudioItem, PlayDirective, Stream};
+
         let a = Directive::Play(PlayDirective {
             play_behavior: PlayBehavior::ReplaceAll,
             audio_item: AudioItem {
                 stream: Stream {
-                    offset_in_milliseconds: 0,
+                    offset_in_milliseconds: crate::request::Offset::ZERO,
                     token: "T".into(),
                     expected_previous_token: None,
                     url: "https://localhost/foo/bar".into(),
@@ -0,0 +1,657 @@
+//! Descriptions of calls to the Alexa service APIs. An [`AlexaApis`] holds the configuration shared by all
+//! requests, such as the retry policy, and is built once outside of the skill's handler; the calls of each
+//! request are then made from its `apiEndpoint`, `apiAccessToken`, device id and locale, with
+//! [`AlexaApis::for_request`] or [`RequestApis::from`]. This crate doesn't include an HTTP client; each method
+//! returns an [`ApiRequest`] for the skill to send with the client of its choice:
+//! ```
+//! # let env: alexa_sdk::RequestEnvelope = serde_json::from_value(serde_json::json!({
+//! #     "version": "1.0",
+//! #     "context": { "System": { "apiEndpoint": "https://api.amazonalexa.com", "apiAccessToken": "TOKEN",
+//! #         "device": { "deviceId": "DEVICE" } } },
+//! #     "request": { "type": "LaunchRequest", "requestId": "id", "timestamp": "2018-12-08T05:37:32Z" }
+//! # })).unwrap();
+//! use alexa_sdk::services::AlexaApis;
+//!
+//! let apis = AlexaApis::new();
+//! // in the handler
+//! let req = apis.for_request(&env).device_address().full_address().unwrap();
+//! assert_eq!(req.url, "https://api.amazonalexa.com/v1/devices/DEVICE/settings/address");
+//! ```
+//!
+//! Error statuses of the responses can be classified with [`ApiError::from_status`], and calls which failed
+//! because of throttling or a server error retried after the delays of the skill's [`RetryPolicy`].
+
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::request::{RequestContext, REDACTED};
+use crate::RequestEnvelope;
+
+/// The endpoint used when a request doesn't carry an `apiEndpoint`
+pub const DEFAULT_API_ENDPOINT: &str = "https://api.amazonalexa.com";
+
+/// An HTTP call to an Alexa service API, ready to be sent. Its `Debug` output doesn't show the
+/// `Authorization` header.
+#[derive(Clone, PartialEq)]
+pub struct ApiRequest {
+    pub method: &'static str,
+    pub url: String,
+    /// includes `Authorization` when the request carried an `apiAccessToken`
+    pub headers: Vec<(&'static str, String)>,
+    /// a JSON body, for `POST` and `PUT` calls
+    pub body: Option<Value>,
+}
+
+impl fmt::Debug for ApiRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers: Vec<(&str, &str)> = self.headers.iter()
+            .map(|(name, value)| match name.eq_ignore_ascii_case("Authorization") {
+                true => (*name, REDACTED),
+                false => (*name, value.as_str()),
+            })
+            .collect();
+        f.debug_struct("ApiRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &headers)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// Configuration shared by the calls to the Alexa service APIs, built once and turned into the calls of
+/// each request with [`AlexaApis::for_request`], see the [module documentation](self). Clones share their
+/// configuration, so an `AlexaApis` is cheap to clone and can be handed to other tasks or threads.
+#[derive(Debug, Clone, Default)]
+pub struct AlexaApis {
+    inner: Arc<ApiConfig>,
+}
+
+#[derive(Debug, Clone)]
+struct ApiConfig {
+    default_endpoint: String,
+    retry_policy: RetryPolicy,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            default_endpoint: String::from(DEFAULT_API_ENDPOINT),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl AlexaApis {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the endpoint used for requests which don't carry an `apiEndpoint`, [`DEFAULT_API_ENDPOINT`]
+    /// unless set
+    pub fn with_default_endpoint(mut self, endpoint: &str) -> Self {
+        Arc::make_mut(&mut self.inner).default_endpoint = endpoint.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the policy for retrying calls made with this configuration
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).retry_policy = policy;
+        self
+    }
+
+    /// the policy for retrying calls made with this configuration, [`RetryPolicy::default`] unless set
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.inner.retry_policy
+    }
+
+    /// The calls available to a request, authorized with its `apiAccessToken` and sent to its `apiEndpoint`
+    pub fn for_request<C: RequestContext>(&self, env: &RequestEnvelope<C>) -> RequestApis {
+        let system = env.context.system();
+        RequestApis {
+            config: self.clone(),
+            endpoint: system.api_endpoint.as_deref()
+                .unwrap_or(&self.inner.default_endpoint)
+                .trim_end_matches('/')
+                .to_string(),
+            token: system.api_access_token.clone(),
+            device_id: env.device_id().cloned(),
+            locale: env.locale().map(|l| l.to_string()),
+        }
+    }
+}
+
+/// The Alexa service APIs for one request, see [`AlexaApis::for_request`]. Its `Debug` output doesn't show
+/// the request's access token.
+#[derive(Clone)]
+pub struct RequestApis {
+    config: AlexaApis,
+    endpoint: String,
+    token: Option<String>,
+    device_id: Option<String>,
+    locale: Option<String>,
+}
+
+impl fmt::Debug for RequestApis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestApis")
+            .field("config", &self.config)
+            .field("endpoint", &self.endpoint)
+            .field("token", &self.token.as_ref().map(|_| REDACTED))
+            .field("device_id", &self.device_id)
+            .field("locale", &self.locale)
+            .finish()
+    }
+}
+
+/// the calls of a request with the default configuration
+impl<C: RequestContext> From<&RequestEnvelope<C>> for RequestApis {
+    fn from(env: &RequestEnvelope<C>) -> Self {
+        AlexaApis::default().for_request(env)
+    }
+}
+
+impl RequestApis {
+    /// the policy for retrying these calls, from the [`AlexaApis`] they were made with
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        self.config.retry_policy()
+    }
+
+    fn request(&self, method: &'static str, path: &str, body: Option<Value>) -> ApiRequest {
+        let mut headers = Vec::new();
+        if let Some(token) = &self.token {
+            headers.push(("Authorization", format!("Bearer {}", token)));
+        }
+        if body.is_some() {
+            headers.push(("Content-Type", String::from("application/json")));
+        }
+        #[cfg(feature = "tracing")]
+        {
+            let path = match self.device_id.as_deref().filter(|id| !id.is_empty()) {
+                Some(id) => path.replace(&segment(id), &crate::request::mask_id(id)),
+                None => path.to_string(),
+            };
+            tracing::debug!(method, endpoint = %self.endpoint, %path, "prepared Alexa API call");
+        }
+        ApiRequest {
+            method,
+            url: format!("{}{}", self.endpoint, path),
+            headers,
+            body,
+        }
+    }
+
+    /// the path of a per-device API, or `None` if the request didn't come from a device
+    fn device_path(&self, version: &str, path: &str) -> Option<String> {
+        let device_id = self.device_id.as_deref().filter(|id| !id.is_empty())?;
+        Some(format!("/{}/devices/{}{}", version, segment(device_id), path))
+    }
+
+    /// the Device Address API, which needs the address or postal code permission
+    pub fn device_address(&self) -> DeviceAddressApi<'_> {
+        DeviceAddressApi { apis: self }
+    }
+
+    /// the Alexa Settings API
+    pub fn settings(&self) -> SettingsApi<'_> {
+        SettingsApi { apis: self }
+    }
+
+    /// the Reminders API, which needs the reminders permission
+    pub fn reminders(&self) -> RemindersApi<'_> {
+        RemindersApi { apis: self }
+    }
+
+    /// the List Management API, which needs the list read or write permission
+    pub fn lists(&self) -> ListsApi<'_> {
+        ListsApi { apis: self }
+    }
+
+    /// the DataStore API, which updates the data of APL widgets and other DataStore packages
+    pub fn datastore(&self) -> DataStoreApi<'_> {
+        DataStoreApi { apis: self }
+    }
+
+    /// the In-Skill Purchasing API
+    pub fn monetization(&self) -> MonetizationApi<'_> {
+        MonetizationApi { apis: self }
+    }
+}
+
+/// An error status returned by an Alexa service API
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// 429: the skill exceeded the rate limit of the API, with the delay requested by `Retry-After`, if any
+    Throttled(Option<Duration>),
+    /// 5xx: the service failed to handle the call
+    ServerError(u16),
+    /// 401 or 403: the access token is invalid, or the user hasn't granted the permission the API needs
+    Unauthorized(u16),
+    /// any other error status
+    Other(u16),
+}
+impl ApiError {
+    /// Classifies the status of a response, with the value of its `Retry-After` header, if any.
+    /// Returns `None` for statuses which aren't errors.
+    pub fn from_status(status: u16, retry_after: Option<&str>) -> Option<Self> {
+        match status {
+            100..=399 => None,
+            429 => Some(Self::Throttled(retry_after.and_then(|s| s.trim().parse().ok()).map(Duration::from_secs))),
+            401 | 403 => Some(Self::Unauthorized(status)),
+            500..=599 => Some(Self::ServerError(status)),
+            _ => Some(Self::Other(status)),
+        }
+    }
+
+    /// whether the same call may succeed if it is retried later
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Throttled(_) | Self::ServerError(_))
+    }
+}
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Throttled(_) => write!(f, "Alexa API call was throttled"),
+            Self::ServerError(status) => write!(f, "Alexa API failed with status {}", status),
+            Self::Unauthorized(status) => write!(f, "Alexa API call was not authorized, status {}", status),
+            Self::Other(status) => write!(f, "Alexa API call was rejected with status {}", status),
+        }
+    }
+}
+impl std::error::Error for ApiError {}
+
+/// Exponential backoff with full jitter for retrying calls which failed with a retryable [`ApiError`]:
+/// ```
+/// use std::time::Duration;
+/// use alexa_sdk::services::{ApiError, RetryPolicy};
+///
+/// let policy = RetryPolicy::new(3).base_delay(Duration::from_millis(200));
+/// let error = ApiError::from_status(503, None).unwrap();
+/// assert!(policy.retry_delay(1, &error).unwrap() <= Duration::from_millis(200));
+/// assert!(policy.retry_delay(2, &error).unwrap() <= Duration::from_millis(400));
+/// assert_eq!(policy.retry_delay(3, &error), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// the number of calls to make in total, including the first one
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+impl Default for RetryPolicy {
+    /// three attempts, with delays starting at 100ms
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// the delay before the first retry, which doubles for each further retry
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// the longest delay before any retry
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// The delay before retrying a call whose attempt number `attempt`, counting from 1, failed with `error`,
+    /// or `None` if the call should not be retried. The delay is random, up to the exponential backoff for the
+    /// attempt, unless the service asked for a specific delay with `Retry-After`. A call is not retried when
+    /// that delay is longer than `max_delay`, since retrying it any earlier would be throttled again.
+    ///
+    /// With the `tracing` feature, emits a `DEBUG` event for each retry and a `WARN` event for each call which
+    /// is given up on.
+    pub fn retry_delay(&self, attempt: u32, error: &ApiError) -> Option<Duration> {
+        let delay = self.next_delay(attempt, error);
+        #[cfg(feature = "tracing")]
+        match delay {
+            Some(delay) => tracing::debug!(attempt, %error, ?delay, "retrying Alexa API call"),
+            None => tracing::warn!(attempt, %error, "Alexa API call failed"),
+        }
+        delay
+    }
+
+    fn next_delay(&self, attempt: u32, error: &ApiError) -> Option<Duration> {
+        if !error.is_retryable() || attempt >= self.max_attempts {
+            return None;
+        }
+        if let ApiError::Throttled(Some(delay)) = error {
+            return Some(*delay).filter(|delay| *delay <= self.max_delay);
+        }
+        let backoff = self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        Some(backoff.mul_f64(jitter()))
+    }
+}
+
+/// Percent-encodes `s` for use as one segment of a path, so that ids supplied by the caller can't
+/// change the path of the call
+fn segment(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => out.push(char::from(b)),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// a random factor in `[0, 1]`, from the random keys std generates for each hasher
+fn jitter() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// The address of the device. Like the [`SettingsApi`], it returns `None` when the request carries no
+/// device id, e.g. for skill events.
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceAddressApi<'a> {
+    apis: &'a RequestApis,
+}
+
+impl DeviceAddressApi<'_> {
+    pub fn full_address(&self) -> Option<ApiRequest> {
+        let path = self.apis.device_path("v1", "/settings/address")?;
+        Some(self.apis.request("GET", &path, None))
+    }
+
+    pub fn country_and_postal_code(&self) -> Option<ApiRequest> {
+        let path = self.apis.device_path("v1", "/settings/address/countryAndPostalCode")?;
+        Some(self.apis.request("GET", &path, None))
+    }
+}
+
+/// The settings of the device, `None` when the request carries no device id
+#[derive(Debug, Clone, Copy)]
+pub struct SettingsApi<'a> {
+    apis: &'a RequestApis,
+}
+
+impl SettingsApi<'_> {
+    fn setting(&self, name: &str) -> Option<ApiRequest> {
+        let path = self.apis.device_path("v2", &format!("/settings/{}", name))?;
+        Some(self.apis.request("GET", &path, None))
+    }
+
+    pub fn time_zone(&self) -> Option<ApiRequest> {
+        self.setting("System.timeZone")
+    }
+
+    pub fn distance_units(&self) -> Option<ApiRequest> {
+        self.setting("System.distanceUnits")
+    }
+
+    pub fn temperature_unit(&self) -> Option<ApiRequest> {
+        self.setting("System.temperatureUnit")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RemindersApi<'a> {
+    apis: &'a RequestApis,
+}
+
+impl RemindersApi<'_> {
+    pub fn list(&self) -> ApiRequest {
+        self.apis.request("GET", "/v1/alerts/reminders", None)
+    }
+
+    pub fn get(&self, alert_token: &str) -> ApiRequest {
+        self.apis.request("GET", &format!("/v1/alerts/reminders/{}", segment(alert_token)), None)
+    }
+
+    pub fn create(&self, reminder: Value) -> ApiRequest {
+        self.apis.request("POST", "/v1/alerts/reminders", Some(reminder))
+    }
+
+    pub fn update(&self, alert_token: &str, reminder: Value) -> ApiRequest {
+        self.apis.request("PUT", &format!("/v1/alerts/reminders/{}", segment(alert_token)), Some(reminder))
+    }
+
+    pub fn delete(&self, alert_token: &str) -> ApiRequest {
+        self.apis.request("DELETE", &format!("/v1/alerts/reminders/{}", segment(alert_token)), None)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ListsApi<'a> {
+    apis: &'a RequestApis,
+}
+
+impl ListsApi<'_> {
+    pub fn metadata(&self) -> ApiRequest {
+        self.apis.request("GET", "/v2/householdlists/", None)
+    }
+
+    /// the items of a list with the given status, `active` or `completed`
+    pub fn get(&self, list_id: &str, status: &str) -> ApiRequest {
+        self.apis.request("GET", &format!("/v2/householdlists/{}/{}", segment(list_id), segment(status)), None)
+    }
+
+    /// one item of a list, e.g. an item reported by a [`crate::request::HouseholdListEvent`]
+    pub fn item(&self, list_id: &str, item_id: &str) -> ApiRequest {
+        self.apis.request("GET", &format!("/v2/householdlists/{}/items/{}", segment(list_id), segment(item_id)), None)
+    }
+
+    pub fn create_item(&self, list_id: &str, item: Value) -> ApiRequest {
+        self.apis.request("POST", &format!("/v2/householdlists/{}/items", segment(list_id)), Some(item))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DataStoreApi<'a> {
+    apis: &'a RequestApis,
+}
+
+impl DataStoreApi<'_> {
+    /// Runs `commands` against the DataStore of the `target` devices or user, e.g. in answer to an
+    /// `Alexa.DataStore.PackageManager.UpdateRequest`. The DataStore API doesn't accept the request's
+    /// `apiAccessToken`: `access_token` must be obtained with the skill's client credentials.
+    pub fn commands(&self, access_token: &str, commands: Vec<DataStoreCommand>, target: DataStoreTarget) -> ApiRequest {
+        let body = serde_json::json!({ "commands": commands, "target": target });
+        let mut req = self.apis.request("POST", "/v1/datastore/commands", Some(body));
+        req.headers.retain(|(name, _)| *name != "Authorization");
+        req.headers.insert(0, ("Authorization", format!("Bearer {}", access_token)));
+        req
+    }
+}
+
+/// A change to the DataStore of a device, see [`DataStoreApi::commands`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DataStoreCommand {
+    PutNamespace { namespace: String },
+    PutObject { namespace: String, key: String, content: Value },
+    UpdateObject { namespace: String, key: String, content: Value },
+    RemoveObject { namespace: String, key: String },
+    RemoveNamespace { namespace: String },
+    /// removes all namespaces
+    Clear,
+}
+
+impl DataStoreCommand {
+    /// Stores `content` under `key`, replacing any existing object
+    pub fn put_object(namespace: &str, key: &str, content: Value) -> Self {
+        Self::PutObject { namespace: String::from(namespace), key: String::from(key), content }
+    }
+
+    pub fn remove_object(namespace: &str, key: &str) -> Self {
+        Self::RemoveObject { namespace: String::from(namespace), key: String::from(key) }
+    }
+}
+
+/// The devices whose DataStore is changed by [`DataStoreApi::commands`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DataStoreTarget {
+    /// every device of the user
+    User { id: String },
+    /// the devices with these ids
+    Devices { items: Vec<String> },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MonetizationApi<'a> {
+    apis: &'a RequestApis,
+}
+
+impl MonetizationApi<'_> {
+    /// the in-skill products available in the request's locale
+    pub fn in_skill_products(&self) -> ApiRequest {
+        let mut req = self.apis.request("GET", "/v1/users/~current/skills/~current/inSkillProducts", None);
+        if let Some(locale) = &self.apis.locale {
+            req.headers.push(("Accept-Language", locale.clone()));
+        }
+        req
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn env(system: Value) -> RequestEnvelope {
+        serde_json::from_value(json!({
+            "version": "1.0",
+            "context": { "System": system },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "de-DE"
+            }
+        })).unwrap()
+    }
+
+    #[test]
+    fn cheap_to_share() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AlexaApis>();
+        assert_send_sync::<RequestApis>();
+
+        let apis = AlexaApis::new().with_retry_policy(RetryPolicy::new(5));
+        let clone = apis.clone();
+        assert!(Arc::ptr_eq(&apis.inner, &clone.inner));
+
+        let env = env(json!({ "apiAccessToken": "TOKEN" }));
+        let (first, second) = (apis.for_request(&env), clone.for_request(&env));
+        assert!(Arc::ptr_eq(&first.config.inner, &second.config.inner));
+        assert_eq!(first.reminders().list(), second.reminders().list());
+        assert_eq!(first.retry_policy(), &RetryPolicy::new(5));
+    }
+
+    #[test]
+    fn retries() {
+        assert_eq!(ApiError::from_status(200, None), None);
+        assert_eq!(ApiError::from_status(429, Some("2")), Some(ApiError::Throttled(Some(Duration::from_secs(2)))));
+        assert_eq!(ApiError::from_status(403, None), Some(ApiError::Unauthorized(403)));
+
+        let apis = AlexaApis::new();
+        let clone = apis.clone().with_retry_policy(RetryPolicy::new(5).max_delay(Duration::from_millis(300)));
+        assert_eq!(apis.retry_policy(), &RetryPolicy::default());
+        let policy = clone.retry_policy();
+
+        let error = ApiError::ServerError(500);
+        for attempt in 1..5 {
+            assert!(policy.retry_delay(attempt, &error).unwrap() <= Duration::from_millis(300));
+        }
+        assert_eq!(policy.retry_delay(5, &error), None);
+        let throttled = ApiError::Throttled(Some(Duration::from_secs(2)));
+        assert_eq!(policy.retry_delay(1, &throttled), None);
+        assert_eq!(apis.retry_policy().retry_delay(1, &throttled), Some(Duration::from_secs(2)));
+        assert_eq!(policy.retry_delay(1, &ApiError::Other(404)), None);
+    }
+
+    #[test]
+    fn datastore_commands() {
+        let apis = RequestApis::from(&env(json!({ "apiAccessToken": "TOKEN" })));
+        let req = apis.datastore().commands(
+            "CLIENT_TOKEN",
+            vec![
+                DataStoreCommand::put_object("scores", "today", json!({ "home": 3, "away": 1 })),
+                DataStoreCommand::remove_object("scores", "yesterday"),
+                DataStoreCommand::Clear,
+            ],
+            DataStoreTarget::User { id: String::from("amzn1.ask.account.USER") },
+        );
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.url, "https://api.amazonalexa.com/v1/datastore/commands");
+        assert_eq!(req.headers[0], ("Authorization", String::from("Bearer CLIENT_TOKEN")));
+        assert!(!req.headers.contains(&("Authorization", String::from("Bearer TOKEN"))));
+        assert_eq!(req.body.unwrap(), json!({
+            "commands": [
+                { "type": "PUT_OBJECT", "namespace": "scores", "key": "today", "content": { "home": 3, "away": 1 } },
+                { "type": "REMOVE_OBJECT", "namespace": "scores", "key": "yesterday" },
+                { "type": "CLEAR" }
+            ],
+            "target": { "type": "USER", "id": "amzn1.ask.account.USER" }
+        }));
+    }
+
+    #[test]
+    fn api_requests() {
+        let apis = RequestApis::from(&env(json!({
+            "apiEndpoint": "https://api.eu.amazonalexa.com/",
+            "apiAccessToken": "TOKEN",
+            "device": { "deviceId": "DEVICE" }
+        })));
+
+        let req = apis.settings().time_zone().unwrap();
+        assert_eq!(req.method, "GET");
+        assert_eq!(req.url, "https://api.eu.amazonalexa.com/v2/devices/DEVICE/settings/System.timeZone");
+        assert_eq!(req.headers, vec![("Authorization", String::from("Bearer TOKEN"))]);
+
+        let req = apis.reminders().create(json!({ "requestTime": "2019-09-22T19:04:00.672" }));
+        assert_eq!(req.method, "POST");
+        assert!(req.headers.contains(&("Content-Type", String::from("application/json"))));
+
+        let req = apis.monetization().in_skill_products();
+        assert!(req.headers.contains(&("Accept-Language", String::from("de-DE"))));
+
+        let req = apis.reminders().delete("alert/../1");
+        assert_eq!(req.url, "https://api.eu.amazonalexa.com/v1/alerts/reminders/alert%2F..%2F1");
+        assert!(format!("{:?}", req).contains(r#"("Authorization", "<redacted>")"#));
+        assert!(!format!("{:?}", apis).contains("TOKEN"));
+
+        let apis = AlexaApis::new().with_default_endpoint("https://localhost:8080/").for_request(&env(json!({})));
+        assert_eq!(apis.reminders().list().url, "https://localhost:8080/v1/alerts/reminders");
+        let apis = RequestApis::from(&env(json!({})));
+        assert_eq!(apis.device_address().full_address(), None);
+        assert_eq!(apis.settings().temperature_unit(), None);
+        let req = apis.lists().metadata();
+        assert_eq!(req.url, "https://api.amazonalexa.com/v2/householdlists/");
+        assert_eq!(apis.lists().item("list", "item-1").url, "https://api.amazonalexa.com/v2/householdlists/list/items/item-1");
+        assert!(req.headers.is_empty());
+    }
+
+    #[test]
+    fn list_item_ids_are_encoded() {
+        let apis = RequestApis::from(&env(json!({})));
+        let req = apis.lists().item("YW16bjEu/shopping", "item/../1 2");
+        assert_eq!(req.url, "https://api.amazonalexa.com/v2/householdlists/YW16bjEu%2Fshopping/items/item%2F..%2F1%202");
+    }
+}
@@ -0,0 +1,281 @@
+//! Helpers for composing SSML for [`crate::response::Speech::ssml`]. The helpers take and return SSML
+//! fragments, so they nest; use [`Ssml::escape`] for any plain text placed inside them:
+//! ```
+//! use alexa_sdk::ssml::{PollyVoice, Ssml};
+//!
+//! let ssml = Ssml::speak(&format!(
+//!     "{} {}",
+//!     Ssml::escape("In Spanish, hello is"),
+//!     Ssml::voice(PollyVoice::Lucia, &Ssml::lang("es-ES", "hola")),
+//! ));
+//! assert_eq!(ssml, r#"<speak>In Spanish, hello is <voice name="Lucia"><lang xml:lang="es-ES">hola</lang></voice></speak>"#);
+//! ```
+
+use std::fmt::Display;
+use std::time::Duration;
+
+#[cfg(feature = "ssml-validation")]
+pub mod validation;
+
+use crate::declare_api_enum;
+use crate::request::{Language, Locale};
+
+/// Namespace for the SSML helper functions
+pub struct Ssml;
+
+impl Ssml {
+    /// wraps a fragment in the `<speak>` root element
+    pub fn speak(inner: &str) -> String {
+        format!("<speak>{}</speak>", inner)
+    }
+
+    /// speaks a fragment with one of the Amazon Polly voices available to Alexa, e.g. `Ssml::voice("Joanna", inner)`
+    pub fn voice<V: Into<PollyVoice>>(voice: V, inner: &str) -> String {
+        format!("<voice name=\"{}\">{}</voice>", voice.into().as_str(), inner)
+    }
+
+    /// speaks a fragment with the pronunciation of another locale, e.g. `Ssml::lang("es-ES", inner)`.
+    /// Accepts a [`crate::request::Locale`] as well as a string.
+    pub fn lang<L: Display>(locale: L, inner: &str) -> String {
+        format!("<lang xml:lang=\"{}\">{}</lang>", locale, inner)
+    }
+
+    /// speaks a duration in the language of `locale`, to the second, e.g. "2 hours and 5 minutes" or
+    /// "2 Stunden und 5 Minuten". Numbers are left as digits, which Alexa reads in the locale's language.
+    /// Languages without a translation are spoken in English.
+    pub fn duration(duration: Duration, locale: &Locale) -> String {
+        DurationWords::of(&locale.language).join(duration, false)
+    }
+
+    /// speaks the time until something happens in the language of `locale`, e.g. "in 2 hours" or
+    /// "in 2 Stunden", see [`Ssml::duration`]
+    pub fn in_duration(duration: Duration, locale: &Locale) -> String {
+        let words = DurationWords::of(&locale.language);
+        words.relative.replace("{}", &words.join(duration, true))
+    }
+
+    /// escapes the characters of plain text which are special in SSML
+    pub fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+}
+
+/// The unit names of one language: days, hours, minutes and seconds. Each unit has one form per plural
+/// category of the language, chosen by `category`, as a pair of patterns: the count on its own and after the
+/// relative preposition. `{}` in a pattern stands for the count; patterns without it replace the count, as
+/// the German "in einem Tag" or the Arabic dual "يومان" do.
+struct DurationWords {
+    units: [&'static [(&'static str, &'static str)]; 4],
+    category: fn(u64) -> usize,
+    comma: &'static str,
+    and: &'static str,
+    relative: &'static str,
+}
+
+/// `0` for a count of one, `1` for any other count
+fn one_or_other(n: u64) -> usize {
+    usize::from(n != 1)
+}
+
+/// Arabic counts of one and two replace the count with the singular and the dual, 3 to 10 take the plural,
+/// and larger counts the accusative singular. Counts of 100 and more, which take the genitive singular,
+/// are spoken with the accusative too.
+fn arabic_category(n: u64) -> usize {
+    match n {
+        1 => 0,
+        2 => 1,
+        3..=10 => 2,
+        _ => 3,
+    }
+}
+
+impl DurationWords {
+    fn of(language: &Language) -> Self {
+        match language {
+            Language::German => Self {
+                units: [
+                    &[("{} Tag", "einem Tag"), ("{} Tage", "{} Tagen")],
+                    &[("{} Stunde", "einer Stunde"), ("{} Stunden", "{} Stunden")],
+                    &[("{} Minute", "einer Minute"), ("{} Minuten", "{} Minuten")],
+                    &[("{} Sekunde", "einer Sekunde"), ("{} Sekunden", "{} Sekunden")],
+                ],
+                category: one_or_other, comma: ", ", and: " und ", relative: "in {}",
+            },
+            Language::French => Self {
+                units: [
+                    &[("{} jour", "{} jour"), ("{} jours", "{} jours")],
+                    &[("{} heure", "{} heure"), ("{} heures", "{} heures")],
+                    &[("{} minute", "{} minute"), ("{} minutes", "{} minutes")],
+                    &[("{} seconde", "{} seconde"), ("{} secondes", "{} secondes")],
+                ],
+                category: one_or_other, comma: ", ", and: " et ", relative: "dans {}",
+            },
+            Language::Spanish => Self {
+                units: [
+                    &[("{} día", "{} día"), ("{} días", "{} días")],
+                    &[("{} hora", "{} hora"), ("{} horas", "{} horas")],
+                    &[("{} minuto", "{} minuto"), ("{} minutos", "{} minutos")],
+                    &[("{} segundo", "{} segundo"), ("{} segundos", "{} segundos")],
+                ],
+                category: one_or_other, comma: ", ", and: " y ", relative: "en {}",
+            },
+            Language::Italian => Self {
+                units: [
+                    &[("{} giorno", "{} giorno"), ("{} giorni", "{} giorni")],
+                    &[("{} ora", "{} ora"), ("{} ore", "{} ore")],
+                    &[("{} minuto", "{} minuto"), ("{} minuti", "{} minuti")],
+                    &[("{} secondo", "{} secondo"), ("{} secondi", "{} secondi")],
+                ],
+                category: one_or_other, comma: ", ", and: " e ", relative: "tra {}",
+            },
+            Language::Portuguese => Self {
+                units: [
+                    &[("{} dia", "{} dia"), ("{} dias", "{} dias")],
+                    &[("{} hora", "{} hora"), ("{} horas", "{} horas")],
+                    &[("{} minuto", "{} minuto"), ("{} minutos", "{} minutos")],
+                    &[("{} segundo", "{} segundo"), ("{} segundos", "{} segundos")],
+                ],
+                category: one_or_other, comma: ", ", and: " e ", relative: "em {}",
+            },
+            Language::Japanese => Self {
+                units: [&[("{}日", "{}日")], &[("{}時間", "{}時間")], &[("{}分", "{}分")], &[("{}秒", "{}秒")]],
+                category: |_| 0, comma: "", and: "", relative: "{}後",
+            },
+            Language::Hindi => Self {
+                units: [
+                    &[("{} दिन", "{} दिन"), ("{} दिन", "{} दिन")],
+                    &[("{} घंटा", "{} घंटे"), ("{} घंटे", "{} घंटे")],
+                    &[("{} मिनट", "{} मिनट"), ("{} मिनट", "{} मिनट")],
+                    &[("{} सेकंड", "{} सेकंड"), ("{} सेकंड", "{} सेकंड")],
+                ],
+                category: one_or_other, comma: ", ", and: " और ", relative: "{} में",
+            },
+            Language::Arabic => Self {
+                units: [
+                    &[("يوم", "يوم"), ("يومان", "يومين"), ("{} أيام", "{} أيام"), ("{} يومًا", "{} يومًا")],
+                    &[("ساعة", "ساعة"), ("ساعتان", "ساعتين"), ("{} ساعات", "{} ساعات"), ("{} ساعة", "{} ساعة")],
+                    &[("دقيقة", "دقيقة"), ("دقيقتان", "دقيقتين"), ("{} دقائق", "{} دقائق"), ("{} دقيقة", "{} دقيقة")],
+                    &[("ثانية", "ثانية"), ("ثانيتان", "ثانيتين"), ("{} ثوان", "{} ثوان"), ("{} ثانية", "{} ثانية")],
+                ],
+                category: arabic_category, comma: "، ", and: " و", relative: "بعد {}",
+            },
+            _ => Self {
+                units: [
+                    &[("{} day", "{} day"), ("{} days", "{} days")],
+                    &[("{} hour", "{} hour"), ("{} hours", "{} hours")],
+                    &[("{} minute", "{} minute"), ("{} minutes", "{} minutes")],
+                    &[("{} second", "{} second"), ("{} seconds", "{} seconds")],
+                ],
+                category: one_or_other, comma: ", ", and: " and ", relative: "in {}",
+            },
+        }
+    }
+
+    fn unit(&self, unit: usize, n: u64, relative: bool) -> String {
+        let (absolute, after_preposition) = self.units[unit][(self.category)(n)];
+        let pattern = if relative { after_preposition } else { absolute };
+        pattern.replace("{}", &n.to_string())
+    }
+
+    fn join(&self, duration: Duration, relative: bool) -> String {
+        let secs = duration.as_secs();
+        let counts = [secs / 86400, secs % 86400 / 3600, secs % 3600 / 60, secs % 60];
+        let mut parts: Vec<String> = counts.iter()
+            .enumerate()
+            .filter(|(_, &n)| n > 0)
+            .map(|(unit, &n)| self.unit(unit, n, relative))
+            .collect();
+        if parts.is_empty() {
+            return self.unit(3, 0, relative);
+        }
+        let last = parts.pop().unwrap_or_default();
+        match (parts.is_empty(), self.and.is_empty()) {
+            (true, _) => last,
+            (false, true) => parts.concat() + &last,
+            (false, false) => parts.join(self.comma) + self.and + &last,
+        }
+    }
+}
+
+declare_api_enum! {
+    PollyVoice {
+        Ivy => "Ivy",
+        Joanna => "Joanna",
+        Joey => "Joey",
+        Justin => "Justin",
+        Kendra => "Kendra",
+        Kimberly => "Kimberly",
+        Matthew => "Matthew",
+        Salli => "Salli",
+        Nicole => "Nicole",
+        Russell => "Russell",
+        Amy => "Amy",
+        Brian => "Brian",
+        Emma => "Emma",
+        Aditi => "Aditi",
+        Raveena => "Raveena",
+        Chantal => "Chantal",
+        Celine => "Celine",
+        Lea => "Lea",
+        Mathieu => "Mathieu",
+        Hans => "Hans",
+        Marlene => "Marlene",
+        Vicki => "Vicki",
+        Carla => "Carla",
+        Giorgio => "Giorgio",
+        Bianca => "Bianca",
+        Mizuki => "Mizuki",
+        Takumi => "Takumi",
+        Conchita => "Conchita",
+        Enrique => "Enrique",
+        Lucia => "Lucia",
+        Mia => "Mia",
+        Miguel => "Miguel",
+        Lupe => "Lupe",
+        Penelope => "Penelope",
+        Camila => "Camila",
+        Ricardo => "Ricardo",
+        Vitoria => "Vitoria"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::locale;
+
+    use super::*;
+
+    #[test]
+    fn voice_and_lang() {
+        assert_eq!(Ssml::voice("Joanna", "hi"), "<voice name=\"Joanna\">hi</voice>");
+        assert_eq!(Ssml::voice("NewVoice", "hi"), "<voice name=\"NewVoice\">hi</voice>");
+        assert_eq!(PollyVoice::from("Hans"), PollyVoice::Hans);
+        assert_eq!(Ssml::lang(locale!(French, Canada), "bonjour"), "<lang xml:lang=\"fr-CA\">bonjour</lang>");
+        assert_eq!(Ssml::speak(&Ssml::escape("Tom & Jerry")), "<speak>Tom &amp; Jerry</speak>");
+    }
+
+    #[test]
+    fn durations() {
+        let d = Duration::from_secs(2 * 3600 + 5 * 60);
+        assert_eq!(Ssml::duration(d, &locale!(English, USA)), "2 hours and 5 minutes");
+        assert_eq!(Ssml::in_duration(Duration::from_secs(2 * 86400), &locale!(German, Germany)), "in 2 Tagen");
+        assert_eq!(Ssml::duration(d, &locale!(German, Germany)), "2 Stunden und 5 Minuten");
+        assert_eq!(Ssml::in_duration(d, &locale!(Japanese, Japan)), "2時間5分後");
+        assert_eq!(Ssml::duration(Duration::from_secs(86400 + 61), &locale!(French, France)), "1 jour, 1 minute et 1 seconde");
+        assert_eq!(Ssml::duration(Duration::from_millis(400), &locale!(Spanish, Spain)), "0 segundos");
+
+        assert_eq!(Ssml::in_duration(Duration::from_secs(86400 + 3600), &locale!(German, Germany)), "in einem Tag und einer Stunde");
+        assert_eq!(Ssml::in_duration(Duration::from_secs(60), &locale!(German, Germany)), "in einer Minute");
+        assert_eq!(Ssml::duration(Duration::from_secs(60), &locale!(German, Germany)), "1 Minute");
+
+        let arabic = locale!(Arabic, SaudiArabia);
+        assert_eq!(Ssml::duration(Duration::from_secs(2 * 3600 + 5 * 60), &arabic), "ساعتان و5 دقائق");
+        assert_eq!(Ssml::in_duration(Duration::from_secs(2 * 86400), &arabic), "بعد يومين");
+        assert_eq!(Ssml::in_duration(Duration::from_secs(3600 + 15 * 60 + 1), &arabic), "بعد ساعة، 15 دقيقة وثانية");
+        assert_eq!(Ssml::duration(Duration::ZERO, &arabic), "0 ثانية");
+    }
+}
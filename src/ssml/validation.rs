@@ -0,0 +1,300 @@
+//! Checks SSML before it is sent to Alexa, which rejects a response whose SSML is malformed or uses tags
+//! it doesn't support, and plays an error instead of the skill's speech:
+//! ```
+//! use alexa_sdk::ssml::validation::{validate, Diagnostic, Problem};
+//!
+//! assert_eq!(validate("<speak>hello <break time=\"1s\"/> world</speak>"), Ok(()));
+//! assert_eq!(
+//!     validate("<speak><emphasis>hello</speak>"),
+//!     Err(vec![Diagnostic { offset: 7, problem: Problem::UnclosedTag(String::from("emphasis")) }])
+//! );
+//! ```
+//! This is a lightweight scanner rather than a full XML parser: it checks that tags are terminated,
+//! balanced and supported, and that entities are escaped. Comments, the XML declaration and other processing
+//! instructions are skipped, and DTDs are not checked.
+
+use std::fmt::Display;
+
+use super::PollyVoice;
+
+/// The tags supported by Alexa, see [the SSML reference](https://developer.amazon.com/en-US/docs/alexa/custom-skills/speech-synthesis-markup-language-ssml-reference.html)
+pub const SUPPORTED_TAGS: &[&str] = &[
+    "amazon:domain", "amazon:effect", "amazon:emotion", "audio", "break", "emphasis", "lang", "p", "phoneme",
+    "prosody", "s", "say-as", "speak", "sub", "voice", "w",
+];
+
+/// Maximum number of `<audio>` clips in one response
+pub const MAX_AUDIO_CLIPS: usize = 5;
+
+/// Maximum duration of a `<break>`, in milliseconds
+pub const MAX_BREAK_MILLIS: u64 = 10_000;
+
+/// A problem found by [`validate`], at a byte offset into the SSML
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub offset: usize,
+    pub problem: Problem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// the SSML isn't wrapped in a `<speak>` element
+    MissingSpeakRoot,
+    /// a `<` has no matching `>`
+    UnterminatedTag,
+    /// a tag's attributes can't be parsed
+    MalformedTag(String),
+    /// an element is opened but never closed
+    UnclosedTag(String),
+    /// a closing tag doesn't match any open element
+    UnexpectedClosingTag(String),
+    /// a tag which Alexa doesn't support, see [`SUPPORTED_TAGS`]
+    UnsupportedTag(String),
+    /// a tag lacks an attribute which Alexa requires
+    MissingAttribute { tag: String, attribute: &'static str },
+    /// a `<voice>` names a voice which isn't a known [`PollyVoice`]
+    UnsupportedVoice(String),
+    /// an `<audio>` source isn't an `https` or `soundbank` url
+    InsecureAudioSource(String),
+    /// the SSML has more than [`MAX_AUDIO_CLIPS`] `<audio>` tags
+    TooManyAudioClips(usize),
+    /// a `<break>` time can't be parsed, or is longer than [`MAX_BREAK_MILLIS`]
+    InvalidBreakTime(String),
+    /// a `&` doesn't start a character or predefined entity reference, use [`super::Ssml::escape`]
+    UnescapedAmpersand,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at byte {}: ", self.offset)?;
+        match &self.problem {
+            Problem::MissingSpeakRoot => write!(f, "SSML must be wrapped in <speak>"),
+            Problem::UnterminatedTag => write!(f, "tag is not terminated by >"),
+            Problem::MalformedTag(t) => write!(f, "malformed attributes in <{}>", t),
+            Problem::UnclosedTag(t) => write!(f, "<{}> is never closed", t),
+            Problem::UnexpectedClosingTag(t) => write!(f, "</{}> doesn't close an open element", t),
+            Problem::UnsupportedTag(t) => write!(f, "<{}> is not supported by Alexa", t),
+            Problem::MissingAttribute { tag, attribute } => write!(f, "<{}> requires a {} attribute", tag, attribute),
+            Problem::UnsupportedVoice(v) => write!(f, "voice {} is not available to Alexa", v),
+            Problem::InsecureAudioSource(s) => write!(f, "audio source {} must be an https url", s),
+            Problem::TooManyAudioClips(n) => write!(f, "SSML has {} audio clips, the maximum is {}", n, MAX_AUDIO_CLIPS),
+            Problem::InvalidBreakTime(t) => write!(f, "break time {} must be between 0 and {}ms", t, MAX_BREAK_MILLIS),
+            Problem::UnescapedAmpersand => write!(f, "& must be escaped as &amp;"),
+        }
+    }
+}
+impl std::error::Error for Diagnostic {}
+
+/// Checks that `ssml` is well formed and only uses what Alexa supports, returning every problem found
+pub fn validate(ssml: &str) -> Result<(), Vec<Diagnostic>> {
+    let mut problems = Vec::new();
+    let mut report = |offset, problem| problems.push(Diagnostic { offset, problem });
+    let mut root = ssml.trim_start();
+    if let Some(end) = root.strip_prefix("<?xml").and_then(|d| d.find("?>")) {
+        root = root[5 + end + 2..].trim_start();
+    }
+    let start = ssml.len() - root.len();
+    if !root.starts_with("<speak") {
+        report(start, Problem::MissingSpeakRoot);
+    }
+
+    let mut open: Vec<(usize, &str)> = Vec::new();
+    let mut audio_clips = 0;
+    let mut i = 0;
+    while let Some(found) = ssml[i..].find(['<', '&']) {
+        let at = i + found;
+        let rest = &ssml[at..];
+        if rest.starts_with('&') {
+            if !is_entity(rest) {
+                report(at, Problem::UnescapedAmpersand);
+            }
+            i = at + 1;
+            continue;
+        }
+        let skipped = [("<!--", "-->"), ("<?", "?>")].into_iter()
+            .find_map(|(open, close)| Some((open, close, rest.strip_prefix(open)?)));
+        if let Some((open, close, body)) = skipped {
+            match body.find(close) {
+                Some(end) => i = at + open.len() + end + close.len(),
+                None => {
+                    report(at, Problem::UnterminatedTag);
+                    break;
+                }
+            }
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            report(at, Problem::UnterminatedTag);
+            break;
+        };
+        i = at + end + 1;
+        let tag = &rest[1..end];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            match open.iter().rposition(|&(_, n)| n == name) {
+                Some(pos) => {
+                    for (offset, unclosed) in open.drain(pos..).skip(1) {
+                        report(offset, Problem::UnclosedTag(String::from(unclosed)));
+                    }
+                }
+                None => report(at, Problem::UnexpectedClosingTag(String::from(name))),
+            }
+            continue;
+        }
+
+        let (tag, self_closing) = match tag.strip_suffix('/') {
+            Some(t) => (t, true),
+            None => (tag, false),
+        };
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = &tag[..name_end];
+        if !self_closing {
+            open.push((at, name));
+        }
+        let Some(attributes) = parse_attributes(&tag[name_end..]) else {
+            report(at, Problem::MalformedTag(String::from(name)));
+            continue;
+        };
+        let attribute = |key: &str| attributes.iter().find(|(k, _)| *k == key).map(|&(_, v)| v);
+        let mut require = |key: &'static str| {
+            let value = attribute(key);
+            if value.is_none() {
+                report(at, Problem::MissingAttribute { tag: String::from(name), attribute: key });
+            }
+            value
+        };
+        match name {
+            "audio" => {
+                audio_clips += 1;
+                if let Some(src) = require("src") {
+                    if !(src.starts_with("https://") || src.starts_with("soundbank://")) {
+                        report(at, Problem::InsecureAudioSource(String::from(src)));
+                    }
+                }
+            }
+            "voice" => {
+                if let Some(voice) = require("name") {
+                    if let PollyVoice::Other(_) = PollyVoice::from(voice) {
+                        report(at, Problem::UnsupportedVoice(String::from(voice)));
+                    }
+                }
+            }
+            "break" => {
+                if let Some(time) = attribute("time") {
+                    if break_millis(time).is_none_or(|ms| ms > MAX_BREAK_MILLIS) {
+                        report(at, Problem::InvalidBreakTime(String::from(time)));
+                    }
+                }
+            }
+            "lang" => { require("xml:lang"); }
+            "phoneme" => { require("ph"); }
+            "say-as" => { require("interpret-as"); }
+            "sub" => { require("alias"); }
+            "amazon:domain" => { require("name"); }
+            "amazon:effect" => { require("name"); }
+            "amazon:emotion" => { require("name"); require("intensity"); }
+            _ if !SUPPORTED_TAGS.contains(&name) => report(at, Problem::UnsupportedTag(String::from(name))),
+            _ => {}
+        }
+    }
+    for (offset, name) in open {
+        report(offset, Problem::UnclosedTag(String::from(name)));
+    }
+    if audio_clips > MAX_AUDIO_CLIPS {
+        report(0, Problem::TooManyAudioClips(audio_clips));
+    }
+
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(problems),
+    }
+}
+
+/// parses `key="value"` pairs, quoted with `"` or `'`
+fn parse_attributes(mut s: &str) -> Option<Vec<(&str, &str)>> {
+    let mut attributes = Vec::new();
+    loop {
+        s = s.trim_start();
+        if s.is_empty() {
+            return Some(attributes);
+        }
+        let (key, rest) = s.split_once('=')?;
+        let rest = rest.trim_start();
+        let quote = rest.chars().next().filter(|&c| c == '"' || c == '\'')?;
+        let (value, rest) = rest[1..].split_once(quote)?;
+        attributes.push((key.trim(), value));
+        s = rest;
+    }
+}
+
+fn is_entity(s: &str) -> bool {
+    let Some(end) = s.find(';') else { return false };
+    match &s[1..end] {
+        "amp" | "lt" | "gt" | "quot" | "apos" => true,
+        e => match e.strip_prefix("#x") {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => e.strip_prefix('#').is_some_and(|d| !d.is_empty() && d.chars().all(|c| c.is_ascii_digit())),
+        },
+    }
+}
+
+fn break_millis(time: &str) -> Option<u64> {
+    match time.strip_suffix("ms") {
+        Some(ms) => ms.parse().ok(),
+        None => time.strip_suffix('s')?.parse::<f64>().ok()
+            .filter(|s| s.is_finite() && *s >= 0.0)
+            .map(|s| (s * 1000.0) as u64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(ssml: &str) -> Vec<Problem> {
+        validate(ssml).err().unwrap_or_default().into_iter().map(|d| d.problem).collect()
+    }
+
+    #[test]
+    fn well_formed() {
+        assert_eq!(validate(r#"<speak><voice name="Hans"><lang xml:lang="de-DE">Hallo</lang></voice> &amp; <!-- <b> --> bye<break time='500ms'/></speak>"#), Ok(()));
+        assert_eq!(problems("hello"), vec![Problem::MissingSpeakRoot]);
+        assert_eq!(problems("<speak>hello</speak"), vec![Problem::UnterminatedTag, Problem::UnclosedTag(String::from("speak"))]);
+        assert_eq!(
+            problems("<speak><p><s>hello</p></s></speak>"),
+            vec![Problem::UnclosedTag(String::from("s")), Problem::UnexpectedClosingTag(String::from("s"))]
+        );
+        assert_eq!(problems("<speak><prosody rate=fast>hi</prosody></speak>"), vec![Problem::MalformedTag(String::from("prosody"))]);
+        assert_eq!(problems("<speak>Tom & Jerry</speak>"), vec![Problem::UnescapedAmpersand]);
+    }
+
+    #[test]
+    fn xml_declaration() {
+        assert_eq!(validate(r#"<?xml version="1.0"?><speak>hello</speak>"#), Ok(()));
+        assert_eq!(validate("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<speak>hello <?pi data?></speak>"), Ok(()));
+        assert_eq!(problems(r#"<?xml version="1.0"?>hello"#), vec![Problem::MissingSpeakRoot]);
+        assert_eq!(problems("<speak>hello <?pi</speak>"), vec![Problem::UnterminatedTag, Problem::UnclosedTag(String::from("speak"))]);
+    }
+
+    #[test]
+    fn alexa_restrictions() {
+        assert_eq!(problems("<speak><b>bold</b></speak>"), vec![Problem::UnsupportedTag(String::from("b"))]);
+        assert_eq!(problems(r#"<speak><voice name="Robot">hi</voice></speak>"#), vec![Problem::UnsupportedVoice(String::from("Robot"))]);
+        assert_eq!(
+            problems(r#"<speak><audio src="http://example.com/a.mp3"/><say-as>1</say-as><break time="11s"/></speak>"#),
+            vec![
+                Problem::InsecureAudioSource(String::from("http://example.com/a.mp3")),
+                Problem::MissingAttribute { tag: String::from("say-as"), attribute: "interpret-as" },
+                Problem::InvalidBreakTime(String::from("11s")),
+            ]
+        );
+        assert_eq!(problems(r#"<speak><break time="-1s"/></speak>"#), vec![Problem::InvalidBreakTime(String::from("-1s"))]);
+        assert_eq!(problems(r#"<speak><break time="-1ms"/></speak>"#), vec![Problem::InvalidBreakTime(String::from("-1ms"))]);
+        assert_eq!(problems(r#"<speak><break time="0.5s"/></speak>"#), vec![]);
+        let clips = r#"<audio src="soundbank://soundlibrary/animals/amzn_sfx_bear_groan_roar_01"/>"#.repeat(6);
+        let diagnostics = validate(&format!("<speak>{}</speak>", clips)).unwrap_err();
+        assert_eq!(diagnostics, vec![Diagnostic { offset: 0, problem: Problem::TooManyAudioClips(6) }]);
+        assert_eq!(diagnostics[0].to_string(), "at byte 0: SSML has 6 audio clips, the maximum is 5");
+    }
+}
@@ -0,0 +1,640 @@
+//! Helpers for exercising skill handlers locally, against request JSON captured from a live skill
+//! (for example from CloudWatch logs, or the developer console simulator).
+//!
+//! ```no_run
+//! use alexa_sdk::{RequestEnvelope, ResponseEnvelope};
+//! use alexa_sdk::testing::invoke_from_file;
+//!
+//! fn handler(_req: RequestEnvelope) -> Result<ResponseEnvelope, std::io::Error> {
+//!     Ok(ResponseEnvelope::simple("hello", "hello world"))
+//! }
+//!
+//! let res = invoke_from_file("captured/launch.json", handler).unwrap();
+//! ```
+//!
+//! [`conformance`] checks the crate's types against the request and response samples of the Alexa
+//! specification, and can be run from a downstream test suite to catch regressions when upgrading this crate:
+//!
+//! ```
+//! assert_eq!(alexa_sdk::testing::conformance(), Ok(()));
+//! ```
+
+use std::error::Error;
+use std::fmt::Display;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{RequestEnvelope, ResponseEnvelope};
+
+/// The error type returned by the helpers in this module, compatible with `lambda_runtime::Error`.
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Reads and deserializes a request envelope from a JSON file.
+pub fn load_request<P: AsRef<Path>>(path: P) -> Result<RequestEnvelope, BoxError> {
+    let bytes = std::fs::read(path.as_ref())
+        .map_err(|e| format!("could not read {}: {}", path.as_ref().display(), e))?;
+    let req = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("could not parse {}: {}", path.as_ref().display(), e))?;
+    Ok(req)
+}
+
+/// Loads the request envelope stored at `path`, runs `handler` on it, and returns the resulting response.
+pub fn invoke_from_file<P, F, E>(path: P, handler: F) -> Result<ResponseEnvelope, BoxError>
+where
+    P: AsRef<Path>,
+    F: FnOnce(RequestEnvelope) -> Result<ResponseEnvelope, E>,
+    E: Into<BoxError>,
+{
+    let req = load_request(path)?;
+    handler(req).map_err(Into::into)
+}
+
+/// Representative payloads, for benchmarks and tests which need realistic requests and responses
+pub mod payloads {
+    use serde_json::json;
+
+    use crate::response::{Card, Speech};
+    use crate::{RequestEnvelope, ResponseEnvelope};
+
+    /// A typical `IntentRequest` with a session, a slot and a device with a screen
+    pub fn intent_request() -> RequestEnvelope {
+        serde_json::from_str(include_str!("testing/fixtures/intent_request.json")).expect("valid fixture")
+    }
+
+    /// An `AudioPlayer.PlaybackNearlyFinished` request, as sent to a skill streaming audio
+    pub fn audioplayer_request() -> RequestEnvelope {
+        serde_json::from_value(json!({
+            "version": "1.0",
+            "context": {
+                "System": {
+                    "application": { "applicationId": "amzn1.ask.skill.APP" },
+                    "user": { "userId": "amzn1.ask.account.USER" },
+                    "device": { "deviceId": "amzn1.ask.device.DEVICE", "supportedInterfaces": { "AudioPlayer": {} } },
+                    "apiEndpoint": "https://api.amazonalexa.com",
+                    "apiAccessToken": "API.TOKEN"
+                },
+                "AudioPlayer": { "token": "track-1", "offsetInMilliseconds": 172000, "playerActivity": "PLAYING" }
+            },
+            "request": {
+                "type": "AudioPlayer.PlaybackNearlyFinished",
+                "requestId": "amzn1.echo-api.request.REQUEST",
+                "timestamp": "2019-03-23T00:34:14Z",
+                "locale": "en-US",
+                "token": "track-1",
+                "offsetInMilliseconds": 172000
+            }
+        })).expect("valid request")
+    }
+
+    /// An `Alexa.Presentation.APL.UserEvent` from a device with a screen, raised by the selection of an item of a
+    /// list, whose visual context describes the `items` visible items of the list, each with its own children
+    pub fn apl_user_event_request(items: usize) -> RequestEnvelope {
+        let children: Vec<serde_json::Value> = (0..items)
+            .map(|i| json!({
+                "uid": format!(":{}", 1000 + i),
+                "id": format!("item-{}", i),
+                "type": "text",
+                "position": format!("1280x120+0+{}:0", i * 120),
+                "tags": { "clickable": true, "ordinal": i + 1 },
+                "children": [
+                    { "uid": format!(":{}", 2000 + i), "type": "graphic", "position": "120x120+0+0:0" },
+                    { "uid": format!(":{}", 3000 + i), "type": "text", "position": "1160x120+120+0:0" }
+                ],
+                "entities": [{ "type": "item", "value": format!("Item {}", i) }]
+            }))
+            .collect();
+        serde_json::from_value(json!({
+            "version": "1.0",
+            "session": {
+                "new": false,
+                "sessionId": "amzn1.echo-api.session.SESSION",
+                "application": { "applicationId": "amzn1.ask.skill.APP" },
+                "user": { "userId": "amzn1.ask.account.USER" }
+            },
+            "context": {
+                "System": {
+                    "application": { "applicationId": "amzn1.ask.skill.APP" },
+                    "user": { "userId": "amzn1.ask.account.USER" },
+                    "device": {
+                        "deviceId": "amzn1.ask.device.DEVICE",
+                        "supportedInterfaces": { "Alexa.Presentation.APL": { "runtime": { "maxVersion": "2023.3" } } }
+                    },
+                    "apiEndpoint": "https://api.amazonalexa.com",
+                    "apiAccessToken": "API.TOKEN"
+                },
+                "Viewport": {
+                    "experiences": [{ "arcMinuteWidth": 346, "arcMinuteHeight": 216, "canRotate": false, "canResize": false }],
+                    "mode": "HUB",
+                    "shape": "RECTANGLE",
+                    "pixelWidth": 1280,
+                    "pixelHeight": 800,
+                    "dpi": 160,
+                    "currentPixelWidth": 1280,
+                    "currentPixelHeight": 800,
+                    "touch": ["SINGLE"],
+                    "video": { "codecs": ["H_264_42", "H_264_41"] }
+                },
+                "Alexa.Presentation.APL": {
+                    "token": "list",
+                    "version": "AriaRuntimeLibrary-2023.3",
+                    "componentsVisibleOnScreen": [{
+                        "uid": ":100",
+                        "id": "list",
+                        "type": "mixed",
+                        "position": "1280x800+0+0:0",
+                        "tags": { "list": { "itemCount": items, "lowestIndexSeen": 0, "highestIndexSeen": items } },
+                        "children": children
+                    }]
+                }
+            },
+            "request": {
+                "type": "Alexa.Presentation.APL.UserEvent",
+                "requestId": "amzn1.echo-api.request.REQUEST",
+                "timestamp": "2019-03-23T00:34:14Z",
+                "locale": "en-US",
+                "token": "list",
+                "arguments": ["item", 3],
+                "source": { "type": "TouchWrapper", "handler": "Press", "id": "item-3" },
+                "components": { "search": "pizza" }
+            }
+        })).expect("valid request")
+    }
+
+    /// A response with plain speech, a simple card and a reprompt
+    pub fn simple_response() -> ResponseEnvelope {
+        ResponseEnvelope::simple("hello", "hello world, what would you like to do next?")
+            .keep_session()
+            .reprompt(Speech::plain("what would you like to do next?"))
+            .card(Card::simple("hello", "hello world"))
+    }
+
+    /// A response which enqueues the next stream
+    #[cfg(feature = "audioplayer")]
+    pub fn audioplayer_response() -> ResponseEnvelope {
+        let mut res = ResponseEnvelope::new(true).should_end_session(None);
+        let play = crate::audioplayer::PlayDirective::enqueue_next(&audioplayer_request(), "https://example.com/track-2.mp3", "track-2")
+            .expect("request has a token");
+        res.add_directive(play.into());
+        res
+    }
+
+    /// A response rendering an APL list of `items` items, each with a title, text and image
+    #[cfg(feature = "apl")]
+    pub fn apl_response(items: usize) -> ResponseEnvelope {
+        use crate::apl::{DataSource, ListDataSource, RenderDocumentDirective};
+
+        let entries: Vec<serde_json::Value> = (0..items)
+            .map(|i| json!({
+                "primaryText": format!("Item {}", i),
+                "secondaryText": "A longer description of the item, as shown below its title in a list",
+                "imageSource": format!("https://example.com/images/{}.png", i),
+            }))
+            .collect();
+        let document = json!({
+            "type": "APL",
+            "version": "2023.3",
+            "import": [{ "name": "alexa-layouts", "version": "1.7.0" }],
+            "mainTemplate": {
+                "parameters": ["payload"],
+                "items": [{
+                    "type": "AlexaImageList",
+                    "listItems": "${payload.list.listPage.listItems}"
+                }]
+            }
+        });
+        let directive = RenderDocumentDirective::new("list", document)
+            .datasource("list", DataSource::List(ListDataSource::new(&entries).expect("serializable items")));
+        let mut res = ResponseEnvelope::simple("list", "here is your list");
+        res.add_directive(directive.into());
+        res
+    }
+}
+
+/// A request and the response a handler returned for it, as recorded by [`Capture`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CapturedExchange {
+    pub request: RequestEnvelope,
+    pub response: ResponseEnvelope,
+}
+
+/// Records a sample of the requests a skill handles in production, and its responses, to a sink such as a
+/// file or a pipe, for replaying hard-to-reproduce dialog states locally or in the simulator. Requests are
+/// recorded with [`RequestEnvelope::redacted`], and every id or token which `redacted` masks is masked the
+/// same way wherever it appears in the response. Exchanges are written one [`CapturedExchange`] JSON object
+/// per line:
+/// ```no_run
+/// use alexa_sdk::{RequestEnvelope, ResponseEnvelope};
+/// use alexa_sdk::testing::Capture;
+///
+/// fn handler(_req: RequestEnvelope) -> Result<ResponseEnvelope, std::io::Error> {
+///     Ok(ResponseEnvelope::simple("hello", "hello world"))
+/// }
+///
+/// let capture = Capture::new(std::fs::File::create("captured.jsonl").unwrap()).sample_one_in(100);
+/// let handler = capture.wrap(handler);
+/// ```
+#[derive(Debug)]
+pub struct Capture<W: Write> {
+    sink: Mutex<W>,
+    one_in: u64,
+}
+
+impl<W: Write> Capture<W> {
+    /// Constructs a capture recording every exchange to `sink`
+    pub fn new(sink: W) -> Self {
+        Self { sink: Mutex::new(sink), one_in: 1 }
+    }
+
+    /// records about one in `n` exchanges, chosen by a hash of the request id, so the same request is
+    /// always either recorded or skipped
+    pub fn sample_one_in(mut self, n: u64) -> Self {
+        self.one_in = n.max(1);
+        self
+    }
+
+    fn sampled(&self, req: &RequestEnvelope) -> bool {
+        // FNV-1a, which unlike the std hashers is stable across Rust releases and platforms
+        let hash = req.request.request_id.bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |h, b| (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3));
+        hash.is_multiple_of(self.one_in)
+    }
+
+    /// records the exchange if it is sampled
+    pub fn record(&self, req: &RequestEnvelope, res: &ResponseEnvelope) -> Result<(), BoxError> {
+        if !self.sampled(req) {
+            return Ok(());
+        }
+        let request = req.redacted();
+        let response = serde_json::from_value(scrub(serde_json::to_value(res)?, &secrets(req, &request)))?;
+        let exchange = CapturedExchange { request, response };
+        let mut line = serde_json::to_vec(&exchange)?;
+        line.push(b'\n');
+        let mut sink = self.sink.lock().map_err(|_| "capture sink lock poisoned")?;
+        sink.write_all(&line)?;
+        sink.flush()?;
+        Ok(())
+    }
+
+    /// Wraps a handler so that its successful responses are recorded. Failures to record are ignored,
+    /// so that capturing never breaks the skill.
+    pub fn wrap<'a, F, E>(&'a self, handler: F) -> impl Fn(RequestEnvelope) -> Result<ResponseEnvelope, E> + 'a
+    where
+        F: Fn(RequestEnvelope) -> Result<ResponseEnvelope, E> + 'a,
+    {
+        move |req| {
+            let captured = self.sampled(&req).then(|| req.clone());
+            let res = handler(req)?;
+            if let Some(req) = captured {
+                let _ = self.record(&req, &res);
+            }
+            Ok(res)
+        }
+    }
+
+    /// returns the sink, e.g. to inspect what was recorded
+    pub fn into_inner(self) -> W {
+        self.sink.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// the ids and tokens of `req` with the values [`RequestEnvelope::redacted`] replaced them by
+fn secrets(req: &RequestEnvelope, redacted: &RequestEnvelope) -> Vec<(String, String)> {
+    fn fields(req: &RequestEnvelope) -> [Option<&String>; 8] {
+        let system = &req.context.system;
+        [
+            system.api_access_token.as_ref(),
+            system.user.as_ref().map(|u| &u.user_id),
+            system.user.as_ref().and_then(|u| u.access_token.as_ref()),
+            system.person.as_ref().map(|p| &p.person_id),
+            system.person.as_ref().and_then(|p| p.access_token.as_ref()),
+            system.device.as_ref().map(|d| &d.device_id),
+            req.session.as_ref().map(|s| &s.user.user_id),
+            req.session.as_ref().and_then(|s| s.user.access_token.as_ref()),
+        ]
+    }
+    fields(req).into_iter()
+        .zip(fields(redacted))
+        .filter_map(|(raw, masked)| Some((raw?.clone(), masked?.clone())))
+        .filter(|(raw, masked)| !raw.is_empty() && raw != masked)
+        .collect()
+}
+
+/// replaces each secret in every string of `value`
+fn scrub(value: Value, secrets: &[(String, String)]) -> Value {
+    match value {
+        Value::String(s) => Value::String(secrets.iter().fold(s, |s, (raw, masked)| s.replace(raw, masked))),
+        Value::Array(a) => Value::Array(a.into_iter().map(|v| scrub(v, secrets)).collect()),
+        Value::Object(o) => Value::Object(o.into_iter().map(|(k, v)| (k, scrub(v, secrets))).collect()),
+        v => v,
+    }
+}
+
+/// A specification sample which doesn't survive a round trip through this crate's types unchanged
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConformanceError {
+    /// the sample could not be deserialized, or was deserialized into the wrong type
+    Unparsable { fixture: &'static str, message: String },
+    /// a field of the sample was renamed, dropped or altered by the round trip
+    Mismatch { fixture: &'static str, path: String, expected: Value, actual: Option<Value> },
+}
+impl Display for ConformanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unparsable { fixture, message } => write!(f, "{}: {}", fixture, message),
+            Self::Mismatch { fixture, path, expected, actual: Some(actual) } =>
+                write!(f, "{}: expected {} at {}, found {}", fixture, expected, path, actual),
+            Self::Mismatch { fixture, path, expected, actual: None } =>
+                write!(f, "{}: expected {} at {}, found nothing", fixture, expected, path),
+        }
+    }
+}
+impl Error for ConformanceError {}
+
+type RoundTrip = fn(Value) -> Result<Value, String>;
+
+fn round_trip<T: serde::de::DeserializeOwned + serde::Serialize>(value: Value) -> Result<Value, String> {
+    let t: T = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    serde_json::to_value(t).map_err(|e| e.to_string())
+}
+
+#[cfg(any(
+    feature = "audioplayer",
+    feature = "apl",
+    feature = "dialog",
+    feature = "monetization",
+    feature = "advertisement"
+))]
+fn round_trip_directive(value: Value) -> Result<Value, String> {
+    match serde_json::from_value(value).map_err(|e| e.to_string())? {
+        crate::response::Directive::Other(_) => Err(String::from("not recognized as a known directive")),
+        directive => serde_json::to_value(directive).map_err(|e| e.to_string()),
+    }
+}
+
+/// round-trips a request of a known type, along with its `body` or `payload` as a `T`
+fn round_trip_event<T: serde::de::DeserializeOwned + serde::Serialize>(value: Value) -> Result<Value, String> {
+    let mut req: RequestEnvelope = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    if let crate::request::RequestType::Other(request_type) = &req.request.request_type {
+        return Err(format!("request type {} not recognized", request_type));
+    }
+    for field in [&mut req.request.body, &mut req.request.payload] {
+        if let Some(value) = field.take() {
+            *field = Some(round_trip::<T>(value)?);
+        }
+    }
+    serde_json::to_value(req).map_err(|e| e.to_string())
+}
+
+/// round-trips a request of a known type whose body, if any, isn't modeled
+fn round_trip_request(value: Value) -> Result<Value, String> {
+    round_trip_event::<Value>(value)
+}
+
+#[cfg(feature = "monetization")]
+fn round_trip_purchase_response(value: Value) -> Result<Value, String> {
+    let req: RequestEnvelope = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    match crate::monetization::PurchaseResponse::from_request(&req) {
+        Some(Ok(_)) => round_trip_request(value),
+        Some(Err(e)) => Err(e.to_string()),
+        None => Err(String::from("not recognized as a purchase response")),
+    }
+}
+
+fn fixtures() -> Vec<(&'static str, &'static str, RoundTrip)> {
+    #[allow(unused_mut)]
+    let mut fixtures: Vec<(&'static str, &'static str, RoundTrip)> = vec![
+        ("intent_request.json", include_str!("testing/fixtures/intent_request.json"), round_trip::<RequestEnvelope>),
+        ("session_ended_request.json", include_str!("testing/fixtures/session_ended_request.json"), round_trip::<RequestEnvelope>),
+        ("response.json", include_str!("testing/fixtures/response.json"), round_trip::<ResponseEnvelope>),
+        ("playback_controller_request.json", include_str!("testing/fixtures/playback_controller_request.json"), round_trip_request),
+        ("exception_encountered_request.json", include_str!("testing/fixtures/exception_encountered_request.json"), round_trip_request),
+        (
+            "authorization_grant_request.json",
+            include_str!("testing/fixtures/authorization_grant_request.json"),
+            round_trip_event::<crate::authorization::AuthorizationGrant>,
+        ),
+        (
+            "reminder_status_changed_request.json",
+            include_str!("testing/fixtures/reminder_status_changed_request.json"),
+            round_trip_event::<crate::request::ReminderEvent>,
+        ),
+        (
+            "reminder_deleted_request.json",
+            include_str!("testing/fixtures/reminder_deleted_request.json"),
+            round_trip_event::<crate::request::ReminderEvent>,
+        ),
+        (
+            "household_list_items_created_request.json",
+            include_str!("testing/fixtures/household_list_items_created_request.json"),
+            round_trip_event::<crate::request::HouseholdListEvent>,
+        ),
+        (
+            "datastore_update_request.json",
+            include_str!("testing/fixtures/datastore_update_request.json"),
+            round_trip_event::<crate::request::DataStorePackageEvent>,
+        ),
+        (
+            "datastore_installation_error_request.json",
+            include_str!("testing/fixtures/datastore_installation_error_request.json"),
+            round_trip_event::<crate::request::DataStorePackageEvent>,
+        ),
+    ];
+    #[cfg(feature = "audioplayer")]
+    fixtures.push(("play_directive.json", include_str!("testing/fixtures/play_directive.json"), round_trip_directive));
+    #[cfg(feature = "apl")]
+    fixtures.extend([
+        ("render_document_directive.json", include_str!("testing/fixtures/render_document_directive.json"), round_trip_directive as RoundTrip),
+        ("execute_commands_directive.json", include_str!("testing/fixtures/execute_commands_directive.json"), round_trip_directive),
+        ("send_token_list_data_directive.json", include_str!("testing/fixtures/send_token_list_data_directive.json"), round_trip_directive),
+        ("aplt_render_document_directive.json", include_str!("testing/fixtures/aplt_render_document_directive.json"), round_trip_directive),
+        ("aplt_execute_commands_directive.json", include_str!("testing/fixtures/aplt_execute_commands_directive.json"), round_trip_directive),
+        ("apl_user_event_request.json", include_str!("testing/fixtures/apl_user_event_request.json"), round_trip_request),
+        ("apl_runtime_error_request.json", include_str!("testing/fixtures/apl_runtime_error_request.json"), round_trip_request),
+        ("apl_load_token_list_data_request.json", include_str!("testing/fixtures/apl_load_token_list_data_request.json"), round_trip_request),
+    ]);
+    #[cfg(feature = "display")]
+    fixtures.extend([
+        ("display_image.json", include_str!("testing/fixtures/display_image.json"), round_trip::<crate::display::Image> as RoundTrip),
+        ("display_element_selected_request.json", include_str!("testing/fixtures/display_element_selected_request.json"), round_trip_request),
+    ]);
+    #[cfg(feature = "dialog")]
+    fixtures.extend([
+        ("delegate_request_directive.json", include_str!("testing/fixtures/delegate_request_directive.json"), round_trip_directive as RoundTrip),
+        ("update_dynamic_entities_directive.json", include_str!("testing/fixtures/update_dynamic_entities_directive.json"), round_trip_directive),
+    ]);
+    #[cfg(feature = "monetization")]
+    fixtures.extend([
+        ("send_request_directive.json", include_str!("testing/fixtures/send_request_directive.json"), round_trip_directive as RoundTrip),
+        ("connections_response_request.json", include_str!("testing/fixtures/connections_response_request.json"), round_trip_purchase_response),
+        ("in_skill_products.json", include_str!("testing/fixtures/in_skill_products.json"), round_trip::<crate::monetization::InSkillProducts>),
+    ]);
+    #[cfg(feature = "advertisement")]
+    fixtures.extend([
+        ("inject_ads_directive.json", include_str!("testing/fixtures/inject_ads_directive.json"), round_trip_directive as RoundTrip),
+        ("ad_completed_request.json", include_str!("testing/fixtures/ad_completed_request.json"), round_trip_request),
+    ]);
+    fixtures
+}
+
+fn compare(fixture: &'static str, path: String, expected: &Value, actual: Option<&Value>, errors: &mut Vec<ConformanceError>) {
+    match (expected, actual) {
+        (Value::Object(expected), Some(Value::Object(actual))) => {
+            for (key, val) in expected {
+                compare(fixture, format!("{}/{}", path, key), val, actual.get(key), errors);
+            }
+        },
+        (Value::Array(expected), Some(Value::Array(actual))) if expected.len() == actual.len() => {
+            for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+                compare(fixture, format!("{}/{}", path, i), e, Some(a), errors);
+            }
+        },
+        (expected, Some(actual)) if expected == actual => {},
+        (expected, actual) => errors.push(ConformanceError::Mismatch {
+            fixture,
+            path,
+            expected: expected.clone(),
+            actual: actual.cloned(),
+        }),
+    }
+}
+
+/// Round-trips samples of the Alexa request and response JSON through the corresponding types of this crate,
+/// for each enabled feature, and reports every field whose name or value doesn't come back as it went in.
+/// Fields added by serialization, such as `null` for absent optional request fields, are not reported.
+///
+/// The samples cover the standard requests and responses, each skill event request with its typed body, and
+/// every directive of the enabled interfaces: AudioPlayer, APL and APLT, Display images, Dialog, in-skill
+/// purchasing and advertising. A request or directive of a type the crate doesn't recognize is reported too.
+pub fn conformance() -> Result<(), Vec<ConformanceError>> {
+    let mut errors = Vec::new();
+    for (fixture, json, round_trip) in fixtures() {
+        let expected: Value = match serde_json::from_str(json) {
+            Ok(v) => v,
+            Err(e) => {
+                errors.push(ConformanceError::Unparsable { fixture, message: e.to_string() });
+                continue;
+            },
+        };
+        match round_trip(expected.clone()) {
+            Ok(actual) => compare(fixture, String::new(), &expected, Some(&actual), &mut errors),
+            Err(message) => errors.push(ConformanceError::Unparsable { fixture, message }),
+        }
+    }
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn invoke_captured_request() {
+        let path = std::env::temp_dir().join(format!("alexa_sdk_invoke_{}.json", std::process::id()));
+        std::fs::write(&path, json!({
+            "version": "1.0",
+            "context": { "System": {} },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US"
+            }
+        }).to_string()).unwrap();
+
+        let res = invoke_from_file(&path, |req| -> Result<ResponseEnvelope, BoxError> {
+            Ok(ResponseEnvelope::simple("locale", req.locale().unwrap().to_string()))
+        });
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(res.unwrap().response.card.unwrap().content.unwrap(), "en-US");
+    }
+
+    #[test]
+    fn capture_exchanges() {
+        let capture = Capture::new(Vec::new());
+        let handler = capture.wrap(|req: RequestEnvelope| -> Result<ResponseEnvelope, BoxError> {
+            Ok(ResponseEnvelope::simple("user", req.user_id().unwrap()))
+        });
+        let req: RequestEnvelope = serde_json::from_value(json!({
+            "version": "1.0",
+            "context": {
+                "System": {
+                    "user": { "userId": "amzn1.ask.account.AGF4EXAMPLE", "accessToken": "secret" }
+                }
+            },
+            "request": {
+                "type": "LaunchRequest",
+                "requestId": "amzn1.echo-api.request.id",
+                "timestamp": "2018-12-08T05:37:32Z",
+                "locale": "en-US"
+            }
+        })).unwrap();
+        handler(req.clone()).unwrap();
+        handler(req).unwrap();
+        drop(handler);
+
+        let recorded = String::from_utf8(capture.into_inner()).unwrap();
+        let lines: Vec<CapturedExchange> = recorded.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].request.context.system.user.as_ref().unwrap().access_token.as_ref().unwrap(), crate::request::REDACTED);
+        assert_eq!(lines[0].response.response.card.as_ref().unwrap().content.as_ref().unwrap(), "amzn1.ask.account.*******MPLE");
+        assert!(!recorded.contains("AGF4EXAMPLE"));
+        assert!(!recorded.contains("secret"));
+
+        let with_id = |i: u32| {
+            let mut req = lines[0].request.clone();
+            req.request.request_id = format!("amzn1.echo-api.request.{}", i);
+            req
+        };
+        let capture = Capture::new(Vec::new()).sample_one_in(4);
+        let sampled: Vec<u32> = (0..20).filter(|&i| capture.sampled(&with_id(i))).collect();
+        assert_eq!(sampled, vec![3, 7, 12, 16]);
+        assert!((0..20).all(|i| Capture::new(Vec::new()).sampled(&with_id(i))));
+    }
+
+    #[test]
+    fn payloads_are_valid() {
+        assert!(payloads::intent_request().slot_value("size").is_some());
+        assert!(payloads::audioplayer_request().context.audio_player.is_some());
+        let event = payloads::apl_user_event_request(5);
+        assert_eq!(event.context.apl.as_ref().unwrap().component("item-4").unwrap().children.len(), 2);
+        assert_eq!(event.user_event().unwrap().source().unwrap().id.as_deref(), Some("item-3"));
+        assert_eq!(payloads::simple_response().validate(), Ok(()));
+        #[cfg(feature = "audioplayer")]
+        assert_eq!(payloads::audioplayer_response().validate(), Ok(()));
+        #[cfg(feature = "apl")]
+        assert_eq!(payloads::apl_response(10).validate(), Ok(()));
+    }
+
+    #[test]
+    fn crate_conforms() {
+        assert_eq!(conformance(), Ok(()));
+    }
+
+    #[test]
+    fn compare_reports_renamed_fields() {
+        let mut errors = Vec::new();
+        let expected = json!({ "a": { "fooBar": 1 }, "b": [1, 2] });
+        let actual = json!({ "a": { "foo_bar": 1 }, "b": [1, 3], "c": null });
+        compare("sample.json", String::new(), &expected, Some(&actual), &mut errors);
+        assert_eq!(errors, vec![
+            ConformanceError::Mismatch { fixture: "sample.json", path: String::from("/a/fooBar"), expected: json!(1), actual: None },
+            ConformanceError::Mismatch { fixture: "sample.json", path: String::from("/b/1"), expected: json!(2), actual: Some(json!(3)) },
+        ]);
+        assert_eq!(errors[0].to_string(), "sample.json: expected 1 at /a/fooBar, found nothing");
+    }
+
+    #[test]
+    fn invoke_missing_file() {
+        let res = invoke_from_file("does/not/exist.json", |_| -> Result<ResponseEnvelope, BoxError> {
+            Ok(ResponseEnvelope::end())
+        });
+        assert!(res.unwrap_err().to_string().contains("does/not/exist.json"));
+    }
+}